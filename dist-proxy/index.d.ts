@@ -1,6 +1,298 @@
 export type JsSyntax = 'ESM' | 'CJS' | 'Mixed' | 'Unknown'
 
-export function guessJsSyntax(s: string): JsSyntax
+export type JsConfidence = 'High' | 'Medium' | 'Low'
+
+export interface JsSyntaxGuess {
+  syntax: JsSyntax
+  /** `'Low'` if nothing was found or the heuristics had to guess */
+  confidence: JsConfidence
+  /** Whether a `/` was guessed to be a division or regex, braces were unbalanced, or something was unterminated */
+  ambiguous: boolean
+}
+
+export type JsFormat =
+  | 'ESM'
+  | 'CJS'
+  | 'UMD'
+  | 'AMD'
+  | 'SystemJS'
+  | 'IIFE'
+  | 'Mixed'
+  | 'Unknown'
+
+/**
+ * The source code, or its UTF-8 bytes, e.g. a `Buffer` from `fs.readFile`. Invalid UTF-8 is
+ * replaced with U+FFFD, so spans are offsets into `buffer.toString()`, not into the bytes. A BOM
+ * is ignored like whitespace.
+ */
+export type JsSource = string | Uint8Array
+
+/** Defaults to `'JS'` */
+export type JsDialect = 'JS' | 'TS' | 'JSX' | 'TSX'
+
+export interface Span {
+  /** UTF-16 offset, can be used to index JS strings */
+  start: number
+  /** UTF-16 offset (exclusive) */
+  end: number
+  /** 1-based line of `start` */
+  line: number
+  /** 0-based UTF-16 column of `start` */
+  column: number
+}
+
+export type JsSyntaxEvidenceKind =
+  | 'Import'
+  | 'ImportMeta'
+  | 'Export'
+  | 'TopLevelAwait'
+  | 'Require'
+  | 'Module'
+  | 'Exports'
+  | 'TsImportEquals'
+  | 'TsExportEquals'
+  | 'TsTypeImport'
+  | 'TsTypeExport'
+  | 'AmdDefine'
+  | 'SystemRegister'
+
+export interface JsSyntaxEvidence {
+  kind: JsSyntaxEvidenceKind
+  span: Span
+  /** The trimmed line the evidence is on */
+  snippet: string
+}
+
+export interface JsDiagnostic {
+  kind:
+    | 'UnterminatedString'
+    | 'UnterminatedTemplate'
+    | 'UnterminatedComment'
+    | 'UnterminatedRegex'
+    | 'AmbiguousSlash'
+    | 'UnbalancedBrace'
+  /** From the opening quote, comment or slash until where scanning gave up. Unclosed braces are at the end of the file */
+  span: Span
+}
+
+export interface JsSyntaxAnalysis {
+  syntax: JsSyntax
+  confidence: JsConfidence
+  ambiguous: boolean
+  format: JsFormat
+  evidence: JsSyntaxEvidence[]
+  /** Unterminated constructs and guesses. Scanning recovers from them, but the result may be incomplete */
+  diagnostics: JsDiagnostic[]
+}
+
+export interface EsmImport {
+  kind: 'Static' | 'Dynamic' | 'Meta'
+  /** `undefined` for `import.meta` and dynamic imports that aren't plain strings */
+  specifier?: string
+  /** The specifier without quotes */
+  specifierSpan?: Span
+  /** The whole statement or expression */
+  span: Span
+}
+
+export interface EsmExport {
+  kind: 'Named' | 'Default' | 'Star'
+  /** The exported name, `undefined` for star exports */
+  name?: string
+  /** The local binding name, if different from the exported name or re-exported */
+  local?: string
+  /** The re-exported module */
+  specifier?: string
+  /** The exported name, or `*` for star exports */
+  span: Span
+}
+
+export interface EsmParseResult {
+  imports: EsmImport[]
+  exports: EsmExport[]
+}
+
+export interface CjsParseResult {
+  /** Names assigned to `exports` or `module.exports`, in order of appearance */
+  exports: string[]
+  /** Specifiers of modules whose exports are re-exported */
+  reexports: string[]
+}
+
+export interface CjsRequire {
+  /** `undefined` if the argument isn't a string or a template literal without expressions */
+  specifier?: string
+  /** The specifier without quotes */
+  specifierSpan?: Span
+  /** The whole `require()` call */
+  span: Span
+  /** Whether the argument is an expression, e.g. `require(name)` */
+  dynamic: boolean
+}
+
+export type JsCommentKind = 'Line' | 'Block' | 'Hashbang' | 'Html'
+
+export interface JsCommentAnnotation {
+  kind:
+    | 'Pure'
+    | 'NoSideEffects'
+    | 'Webpack'
+    | 'ViteIgnore'
+    | 'Legal'
+    | 'SourceMappingUrl'
+  /** The annotation as written, e.g. `#__PURE__`, `webpackChunkName` or `@license` */
+  name: string
+  /** The raw value, e.g. `"foo"` of `webpackChunkName: "foo"` or the URL of a source map */
+  value?: string
+}
+
+export interface JsComment {
+  kind: JsCommentKind
+  /** The content without `//`, `/*`, `*\/`, `#!`, `<!--` or `-->` */
+  text: string
+  span: Span
+  annotations: JsCommentAnnotation[]
+}
+
+export interface JsSourceMapComment {
+  kind: 'SourceMappingUrl' | 'SourceUrl'
+  url: string
+  /** The decoded source map if `url` is an inline `data:application/json` URI */
+  data?: string
+  /** The whole comment */
+  span: Span
+}
+
+export interface JsSourceMapComments {
+  comments: JsSourceMapComment[]
+  /** The source without the comments, if `strip` is set */
+  code?: string
+}
+
+export interface JsNodeFormat {
+  /** How Node loads the file */
+  format: 'ESM' | 'CJS'
+  /** `Extension` for `.mjs` and `.cjs`, `PackageType` for the `"type"` field, or `Default` */
+  reason: 'Extension' | 'PackageType' | 'Default'
+  /** What the code looks like */
+  syntax: JsSyntax
+  /** Whether the syntax doesn't work in the format, e.g. `import` in a CJS file */
+  mismatch: boolean
+}
+
+export interface JsPackageResolution {
+  /**
+   * The file relative to the package, e.g. `./dist/index.mjs`, or a bare specifier for
+   * `imports` that map to another package
+   */
+  target?: string
+  /** Why it couldn't be resolved, named after Node's error codes */
+  error?:
+    | 'InvalidPackageConfig'
+    | 'InvalidPackageTarget'
+    | 'InvalidModuleSpecifier'
+    | 'PackagePathNotExported'
+    | 'PackageImportNotDefined'
+}
+
+export interface JsDirective {
+  /** The raw content without quotes, e.g. `use strict` */
+  name: string
+  /** The string literal with quotes */
+  span: Span
+}
+
+export function guessJsSyntax(s: JsSource, dialect?: JsDialect): JsSyntax
+
+export function guessJsSyntaxWithConfidence(
+  s: JsSource,
+  dialect?: JsDialect
+): JsSyntaxGuess
+
+export function guessJsFormat(s: JsSource, dialect?: JsDialect): JsFormat
+
+/**
+ * Detect the syntax of a file that's read in chunks, e.g. from a `ReadStream`
+ */
+export class JsSyntaxStream {
+  constructor(dialect?: JsDialect)
+  /**
+   * Add the next chunk of UTF-8 bytes. Returns `true` once both ESM and CJS was found, so the
+   * rest of the file doesn't need to be read
+   */
+  push(chunk: Uint8Array): boolean
+  /** The syntax found so far */
+  syntax(): JsSyntax
+  /** Scan the rest of the file. The stream can't be used afterwards */
+  finish(): JsSyntax
+}
+
+export function analyzeJsSyntax(
+  s: JsSource,
+  dialect?: JsDialect
+): JsSyntaxAnalysis
+
+export function parseEsm(s: JsSource, dialect?: JsDialect): EsmParseResult
+
+export function parseCjs(s: JsSource, dialect?: JsDialect): CjsParseResult
+
+export function parseRequires(s: JsSource, dialect?: JsDialect): CjsRequire[]
+
+/**
+ * Find all comments, with annotations like `/*#__PURE__*\/` or `/* webpackChunkName: "foo" *\/`
+ */
+export function parseComments(s: JsSource, dialect?: JsDialect): JsComment[]
+
+/**
+ * Find the `//# sourceMappingURL=` and `//# sourceURL=` comments at the end of the file.
+ * Comments followed by other code are ignored, like browsers and Node do.
+ */
+export function parseSourceMapComments(
+  s: JsSource,
+  options?: { dialect?: JsDialect; strip?: boolean }
+): JsSourceMapComments
+
+/**
+ * Compare how Node loads the file, from its extension and the `"type"` of the nearest
+ * package.json, with what its code looks like. Returns `undefined` for files that aren't JS.
+ */
+export function checkNodeFormat(
+  path: string,
+  s: JsSource,
+  packageJson?: string
+): JsNodeFormat | undefined
+
+/**
+ * Resolve `pkg`, `pkg/sub/path` or `#internal` with the `exports` or `imports` of the package's
+ * package.json, like Node does for the conditions, e.g. `['node', 'import']`. `default` always
+ * matches.
+ */
+export function resolvePackage(
+  packageJson: string | Uint8Array,
+  specifier: string,
+  conditions?: string[]
+): JsPackageResolution
+
+/**
+ * Read the directive prologue, the string literals at the start of the file, after the
+ * hashbang and comments.
+ */
+export function directives(s: JsSource): JsDirective[]
+
+/**
+ * The `#!` line at the start of the file without `#!`, e.g. `/usr/bin/env node`
+ */
+export function hashbang(s: JsSource): string | undefined
+
+/**
+ * Replace every module specifier in imports, exports and requires. Return `undefined`
+ * from the function, or omit the specifier from the mapping, to keep it as is.
+ */
+export function rewriteSpecifiers(
+  s: JsSource,
+  rewriter: ((specifier: string) => string | undefined) | Record<string, string>,
+  dialect?: JsDialect
+): string
 
 export {
   default as init,
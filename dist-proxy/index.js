@@ -1,20 +1,358 @@
 import init, {
   initSync,
-  guessJsSyntax as _guessJsSyntax
+  JsSyntaxStream as _JsSyntaxStream,
+  guessJsSyntaxWithDialect as _guessJsSyntaxString,
+  guessJsSyntaxBytes as _guessJsSyntaxBytes,
+  analyzeJsSyntaxWithDialect as _analyzeJsSyntaxString,
+  analyzeJsSyntaxBytes as _analyzeJsSyntaxBytes,
+  guessJsSyntaxWithConfidence as _guessJsSyntaxWithConfidenceString,
+  guessJsSyntaxWithConfidenceBytes as _guessJsSyntaxWithConfidenceBytes,
+  guessJsFormatWithDialect as _guessJsFormatString,
+  guessJsFormatBytes as _guessJsFormatBytes,
+  parseEsm as _parseEsmString,
+  parseEsmBytes as _parseEsmBytes,
+  parseCjs as _parseCjsString,
+  parseCjsBytes as _parseCjsBytes,
+  parseRequires as _parseRequiresString,
+  parseRequiresBytes as _parseRequiresBytes,
+  parseComments as _parseCommentsString,
+  parseCommentsBytes as _parseCommentsBytes,
+  parseSourceMapComments as _parseSourceMapCommentsString,
+  parseSourceMapCommentsBytes as _parseSourceMapCommentsBytes,
+  checkNodeFormat as _checkNodeFormatString,
+  checkNodeFormatBytes as _checkNodeFormatBytes,
+  resolvePackage as _resolvePackageString,
+  resolvePackageBytes as _resolvePackageBytes,
+  directives as _directivesString,
+  directivesBytes as _directivesBytes,
+  hashbang as _hashbangString,
+  hashbangBytes as _hashbangBytes,
+  rewriteSpecifiers as _rewriteSpecifiersString,
+  rewriteSpecifiersBytes as _rewriteSpecifiersBytes
 } from '../dist/index.js'
 
-export function guessJsSyntax(s) {
-  const result = _guessJsSyntax(s)
-  switch (result) {
-    case 0:
-      return 'ESM'
-    case 1:
-      return 'CJS'
-    case 2:
-      return 'Mixed'
-    case 3:
-      return 'Unknown'
+// accept both strings and Uint8Array, e.g. a `Buffer` from `fs.readFile`,
+// which skips decoding the file into a string
+function withBytes(fn, bytesFn) {
+  return (s, ...args) => (typeof s === 'string' ? fn : bytesFn)(s, ...args)
+}
+
+const _guessJsSyntax = withBytes(_guessJsSyntaxString, _guessJsSyntaxBytes)
+const _analyzeJsSyntax = withBytes(
+  _analyzeJsSyntaxString,
+  _analyzeJsSyntaxBytes
+)
+const _guessJsSyntaxWithConfidence = withBytes(
+  _guessJsSyntaxWithConfidenceString,
+  _guessJsSyntaxWithConfidenceBytes
+)
+const _guessJsFormat = withBytes(_guessJsFormatString, _guessJsFormatBytes)
+const _parseEsm = withBytes(_parseEsmString, _parseEsmBytes)
+const _parseCjs = withBytes(_parseCjsString, _parseCjsBytes)
+const _parseRequires = withBytes(_parseRequiresString, _parseRequiresBytes)
+const _parseComments = withBytes(_parseCommentsString, _parseCommentsBytes)
+const _parseSourceMapComments = withBytes(
+  _parseSourceMapCommentsString,
+  _parseSourceMapCommentsBytes
+)
+const _resolvePackage = withBytes(
+  _resolvePackageString,
+  _resolvePackageBytes
+)
+const _directives = withBytes(_directivesString, _directivesBytes)
+const _hashbang = withBytes(_hashbangString, _hashbangBytes)
+const _rewriteSpecifiers = withBytes(
+  _rewriteSpecifiersString,
+  _rewriteSpecifiersBytes
+)
+
+const jsSyntaxes = ['ESM', 'CJS', 'Mixed', 'Unknown']
+const jsFormats = [
+  'ESM',
+  'CJS',
+  'UMD',
+  'AMD',
+  'SystemJS',
+  'IIFE',
+  'Mixed',
+  'Unknown'
+]
+const jsConfidences = ['High', 'Medium', 'Low']
+const jsDialects = ['JS', 'TS', 'JSX', 'TSX']
+const jsSyntaxEvidenceKinds = [
+  'Import',
+  'ImportMeta',
+  'Export',
+  'TopLevelAwait',
+  'Require',
+  'Module',
+  'Exports',
+  'TsImportEquals',
+  'TsExportEquals',
+  'TsTypeImport',
+  'TsTypeExport',
+  'AmdDefine',
+  'SystemRegister'
+]
+const jsDiagnosticKinds = [
+  'UnterminatedString',
+  'UnterminatedTemplate',
+  'UnterminatedComment',
+  'UnterminatedRegex',
+  'AmbiguousSlash',
+  'UnbalancedBrace'
+]
+const jsCommentKinds = ['Line', 'Block', 'Hashbang', 'Html']
+const jsCommentAnnotationKinds = [
+  'Pure',
+  'NoSideEffects',
+  'Webpack',
+  'ViteIgnore',
+  'Legal',
+  'SourceMappingUrl'
+]
+const jsSourceMapCommentKinds = ['SourceMappingUrl', 'SourceUrl']
+const jsNodeFormatReasons = ['Extension', 'PackageType', 'Default']
+const jsResolveErrors = [
+  'InvalidPackageConfig',
+  'InvalidPackageTarget',
+  'InvalidModuleSpecifier',
+  'PackagePathNotExported',
+  'PackageImportNotDefined'
+]
+const esmImportKinds = ['Static', 'Dynamic', 'Meta']
+const esmExportKinds = ['Named', 'Default', 'Star']
+
+// spans from wasm have utf-8 byte offsets, convert to utf-16 offsets for js strings
+function toSpan(span) {
+  if (span == null) return undefined
+  const result = {
+    start: span.utf16_start,
+    end: span.utf16_end,
+    line: span.line,
+    column: span.column
+  }
+  span.free()
+  return result
+}
+
+function toJsDialect(dialect = 'JS') {
+  const index = jsDialects.indexOf(dialect)
+  if (index === -1) throw new Error(`Unknown dialect: ${dialect}`)
+  return index
+}
+
+export function guessJsSyntax(s, dialect) {
+  return jsSyntaxes[_guessJsSyntax(s, toJsDialect(dialect))]
+}
+
+export function guessJsSyntaxWithConfidence(s, dialect) {
+  const guess = _guessJsSyntaxWithConfidence(s, toJsDialect(dialect))
+  const result = {
+    syntax: jsSyntaxes[guess.syntax],
+    confidence: jsConfidences[guess.confidence],
+    ambiguous: guess.ambiguous
+  }
+  guess.free()
+  return result
+}
+
+export function guessJsFormat(s, dialect) {
+  return jsFormats[_guessJsFormat(s, toJsDialect(dialect))]
+}
+
+export class JsSyntaxStream {
+  #stream
+
+  constructor(dialect) {
+    this.#stream = new _JsSyntaxStream(toJsDialect(dialect))
+  }
+
+  push(chunk) {
+    return this.#stream.push(chunk)
+  }
+
+  syntax() {
+    return jsSyntaxes[this.#stream.syntax()]
+  }
+
+  finish() {
+    // consumes the wasm object
+    return jsSyntaxes[this.#stream.finish()]
+  }
+}
+
+export function analyzeJsSyntax(s, dialect) {
+  const analysis = _analyzeJsSyntax(s, toJsDialect(dialect))
+  const result = {
+    syntax: jsSyntaxes[analysis.syntax],
+    confidence: jsConfidences[analysis.confidence],
+    ambiguous: analysis.ambiguous,
+    format: jsFormats[analysis.format],
+    evidence: analysis.evidence.map((e) => {
+      const evidence = {
+        kind: jsSyntaxEvidenceKinds[e.kind],
+        span: toSpan(e.span),
+        snippet: e.snippet
+      }
+      e.free()
+      return evidence
+    }),
+    diagnostics: analysis.diagnostics.map((d) => {
+      const diagnostic = {
+        kind: jsDiagnosticKinds[d.kind],
+        span: toSpan(d.span)
+      }
+      d.free()
+      return diagnostic
+    })
   }
+  analysis.free()
+  return result
+}
+
+export function parseEsm(s, dialect) {
+  const parsed = _parseEsm(s, toJsDialect(dialect))
+  const result = {
+    imports: parsed.imports.map((i) => {
+      const esmImport = {
+        kind: esmImportKinds[i.kind],
+        specifier: i.specifier,
+        specifierSpan: toSpan(i.specifier_span),
+        span: toSpan(i.span)
+      }
+      i.free()
+      return esmImport
+    }),
+    exports: parsed.exports.map((e) => {
+      const esmExport = {
+        kind: esmExportKinds[e.kind],
+        name: e.name,
+        local: e.local,
+        specifier: e.specifier,
+        span: toSpan(e.span)
+      }
+      e.free()
+      return esmExport
+    })
+  }
+  parsed.free()
+  return result
+}
+
+export function parseCjs(s, dialect) {
+  const parsed = _parseCjs(s, toJsDialect(dialect))
+  const result = { exports: parsed.exports, reexports: parsed.reexports }
+  parsed.free()
+  return result
+}
+
+export function parseRequires(s, dialect) {
+  return _parseRequires(s, toJsDialect(dialect)).map((r) => {
+    const cjsRequire = {
+      specifier: r.specifier,
+      specifierSpan: toSpan(r.specifier_span),
+      span: toSpan(r.span),
+      dynamic: r.dynamic
+    }
+    r.free()
+    return cjsRequire
+  })
+}
+
+export function parseComments(s, dialect) {
+  return _parseComments(s, toJsDialect(dialect)).map((c) => {
+    const comment = {
+      kind: jsCommentKinds[c.kind],
+      text: c.text,
+      span: toSpan(c.span),
+      annotations: c.annotations.map((a) => {
+        const annotation = {
+          kind: jsCommentAnnotationKinds[a.kind],
+          name: a.name,
+          value: a.value
+        }
+        a.free()
+        return annotation
+      })
+    }
+    c.free()
+    return comment
+  })
+}
+
+export function parseSourceMapComments(s, options = {}) {
+  const result = _parseSourceMapComments(
+    s,
+    toJsDialect(options.dialect),
+    !!options.strip
+  )
+  const sourceMapComments = {
+    comments: result.comments.map((c) => {
+      const comment = {
+        kind: jsSourceMapCommentKinds[c.kind],
+        url: c.url,
+        data: c.data,
+        span: toSpan(c.span)
+      }
+      c.free()
+      return comment
+    }),
+    code: result.code
+  }
+  result.free()
+  return sourceMapComments
+}
+
+export function checkNodeFormat(path, s, packageJson) {
+  const check =
+    typeof s === 'string' ? _checkNodeFormatString : _checkNodeFormatBytes
+  const result = check(path, s, packageJson)
+  if (result == null) return undefined
+  const nodeFormat = {
+    format: jsSyntaxes[result.format],
+    reason: jsNodeFormatReasons[result.reason],
+    syntax: jsSyntaxes[result.syntax],
+    mismatch: result.mismatch
+  }
+  result.free()
+  return nodeFormat
+}
+
+export function resolvePackage(packageJson, specifier, conditions = []) {
+  const result = _resolvePackage(packageJson, specifier, conditions)
+  const resolution = {
+    target: result.target,
+    error: result.error == null ? undefined : jsResolveErrors[result.error]
+  }
+  result.free()
+  return resolution
+}
+
+export function directives(s) {
+  return _directives(s).map((d) => {
+    const directive = {
+      name: d.name,
+      span: toSpan(d.span)
+    }
+    d.free()
+    return directive
+  })
+}
+
+export function rewriteSpecifiers(s, rewriter, dialect) {
+  if (typeof rewriter === 'function') {
+    return _rewriteSpecifiers(s, toJsDialect(dialect), rewriter)
+  }
+  return _rewriteSpecifiers(s, toJsDialect(dialect), (specifier) =>
+    Object.prototype.hasOwnProperty.call(rewriter, specifier)
+      ? rewriter[specifier]
+      : undefined
+  )
+}
+
+export function hashbang(s) {
+  return _hashbang(s)
 }
 
 export { init, initSync }
@@ -0,0 +1,465 @@
+use fmu::{
+    analyze_js_syntax_with_dialect, check_package_hazards_dir, glob_match, JsDialect, PackageHazard,
+};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
+
+const HELP: &str = "\
+Detect the module syntax of JavaScript and TypeScript files
+
+Usage: fmu [options] <paths...>
+       fmu hazard [--output <table|json|ndjson>] <package dirs...>
+
+Paths can be files, directories or globs like 'src/**/*.js'. Directories are searched for
+.js, .mjs, .cjs, .jsx, .ts, .mts, .cts and .tsx files, skipping node_modules and hidden
+directories. Globs only search those when the pattern names them, e.g. 'node_modules/**/*.js'.
+
+'fmu hazard' checks the `exports` of packages for the dual package hazard, where `import`
+and `require` load different files, for `require` targets that are ESM and for `import`
+targets that are CJS.
+
+Options:
+  --output <table|json|ndjson>  How to print the results (default: table)
+  --deny <name>                 Exit with 1 if a file has the syntax or format: ESM, CJS, UMD,
+                                AMD, SystemJS, IIFE, Mixed or Unknown. Can be repeated
+  --dialect <JS|TS|JSX|TSX>     Dialect of all files, instead of guessing from the extension
+  -h, --help                    Print this help
+
+Exit codes: 0 on success, 1 if a denied syntax or format or a hazard was found, 2 on errors";
+
+// the names of the syntaxes and formats that --deny accepts
+const DENY_NAMES: [&str; 8] = [
+    "ESM", "CJS", "UMD", "AMD", "SystemJS", "IIFE", "Mixed", "Unknown",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Table,
+    Json,
+    Ndjson,
+}
+
+struct Options {
+    // `fmu hazard`
+    hazard: bool,
+    paths: Vec<String>,
+    output: Output,
+    deny: Vec<&'static str>,
+    dialect: Option<JsDialect>,
+}
+
+struct FileResult {
+    path: String,
+    syntax: String,
+    format: String,
+    confidence: String,
+    ambiguous: bool,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", HELP);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("fmu: {}\n\n{}", message, HELP);
+            return ExitCode::from(2);
+        }
+    };
+
+    if options.hazard {
+        return check_hazards(&options);
+    }
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for path in &options.paths {
+        collect_files(path, &mut files, &mut errors);
+    }
+    for message in &errors {
+        eprintln!("fmu: {}", message);
+    }
+    let mut has_error = !errors.is_empty();
+    // overlapping paths, e.g. `src/*.js` and `**/*.js`, find the same files
+    files.sort();
+    files.dedup();
+
+    let mut results = Vec::new();
+    for file in files {
+        let dialect = options.dialect.unwrap_or_else(|| dialect_of(&file));
+        let s = match fs::read(&file) {
+            Ok(b) => String::from_utf8_lossy(&b).into_owned(),
+            Err(err) => {
+                eprintln!("fmu: {}: {}", file.display(), err);
+                has_error = true;
+                continue;
+            }
+        };
+        let analysis = analyze_js_syntax_with_dialect(&s, dialect);
+        results.push(FileResult {
+            path: file.to_string_lossy().replace('\\', "/"),
+            syntax: format!("{:?}", analysis.syntax),
+            format: format!("{:?}", analysis.format),
+            confidence: format!("{:?}", analysis.confidence),
+            ambiguous: analysis.ambiguous,
+        });
+    }
+
+    match options.output {
+        Output::Table => print_table(&results),
+        Output::Json => println!(
+            "[{}]",
+            results.iter().map(to_json).collect::<Vec<_>>().join(",")
+        ),
+        Output::Ndjson => results.iter().for_each(|r| println!("{}", to_json(r))),
+    }
+
+    let denied: Vec<&FileResult> = results
+        .iter()
+        .filter(|r| {
+            options
+                .deny
+                .iter()
+                .any(|&name| name == r.syntax || name == r.format)
+        })
+        .collect();
+    for r in &denied {
+        eprintln!(
+            "fmu: {} is {} ({}), which is denied",
+            r.path, r.syntax, r.format
+        );
+    }
+
+    if has_error {
+        ExitCode::from(2)
+    } else if !denied.is_empty() {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// returns `None` for --help
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let hazard = args.first().is_some_and(|arg| arg == "hazard");
+    let mut options = Options {
+        hazard,
+        paths: Vec::new(),
+        output: Output::Table,
+        deny: Vec::new(),
+        dialect: None,
+    };
+
+    let mut args = args[usize::from(hazard)..].iter();
+    while let Some(arg) = args.next() {
+        // --output=json or --output json
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("missing value for {}", name))
+        };
+
+        match name {
+            "-h" | "--help" => return Ok(None),
+            "--output" => {
+                options.output = match value()?.as_str() {
+                    "table" => Output::Table,
+                    "json" => Output::Json,
+                    "ndjson" => Output::Ndjson,
+                    other => return Err(format!("unknown output: {}", other)),
+                }
+            }
+            "--deny" if !hazard => {
+                let value = value()?;
+                match DENY_NAMES.iter().find(|v| v.eq_ignore_ascii_case(&value)) {
+                    Some(name) => options.deny.push(name),
+                    None => return Err(format!("unknown syntax or format: {}", value)),
+                }
+            }
+            "--dialect" if !hazard => {
+                options.dialect = Some(match value()?.to_ascii_uppercase().as_str() {
+                    "JS" => JsDialect::JS,
+                    "TS" => JsDialect::TS,
+                    "JSX" => JsDialect::JSX,
+                    "TSX" => JsDialect::TSX,
+                    other => return Err(format!("unknown dialect: {}", other)),
+                })
+            }
+            _ if name.starts_with('-') && name.len() > 1 => {
+                return Err(format!("unknown option: {}", name))
+            }
+            _ => options.paths.push(arg.clone()),
+        }
+    }
+
+    if options.paths.is_empty() {
+        return Err("no paths given".to_string());
+    }
+    Ok(Some(options))
+}
+
+fn check_hazards(options: &Options) -> ExitCode {
+    let mut has_error = false;
+    let mut results = Vec::new();
+    for dir in &options.paths {
+        match check_package_hazards_dir(Path::new(dir)) {
+            Ok(hazards) => results.extend(hazards.into_iter().map(|h| (dir.as_str(), h))),
+            Err(err) => {
+                eprintln!("fmu: {}: {}", dir, err);
+                has_error = true;
+            }
+        }
+    }
+
+    match options.output {
+        Output::Table => print_hazard_table(&results),
+        Output::Json => println!(
+            "[{}]",
+            results
+                .iter()
+                .map(|(dir, h)| hazard_to_json(dir, h))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Output::Ndjson => results
+            .iter()
+            .for_each(|(dir, h)| println!("{}", hazard_to_json(dir, h))),
+    }
+
+    if has_error {
+        ExitCode::from(2)
+    } else if !results.is_empty() {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn dialect_of(path: &Path) -> JsDialect {
+    path.extension()
+        .and_then(|ext| JsDialect::from_extension(&ext.to_string_lossy()))
+        .unwrap_or(JsDialect::JS)
+}
+
+// add the files of a path to `files`, and the paths that couldn't be read to `errors`
+fn collect_files(path: &str, files: &mut Vec<PathBuf>, errors: &mut Vec<String>) {
+    if !is_glob(path) {
+        let path = PathBuf::from(path);
+        if path.is_dir() {
+            walk_dir(
+                &path,
+                &[],
+                &mut |file| {
+                    let extension = file.extension().unwrap_or_default().to_string_lossy();
+                    if JsDialect::from_extension(&extension).is_some() {
+                        files.push(normalize(file));
+                    }
+                },
+                errors,
+            );
+        } else if path.is_file() {
+            files.push(normalize(&path));
+        } else {
+            errors.push(format!("{}: no such file or directory", path.display()));
+        }
+        return;
+    }
+
+    // search from the directories before the first glob, e.g. `src` of `src/**/*.js`
+    let pattern = path.replace('\\', "/");
+    let pattern = pattern.strip_prefix("./").unwrap_or(&pattern);
+    let base: Vec<&str> = pattern.split('/').take_while(|v| !is_glob(v)).collect();
+    let base = match base.join("/").as_str() {
+        "" if pattern.starts_with('/') => PathBuf::from("/"),
+        "" => PathBuf::from("."),
+        base => PathBuf::from(base),
+    };
+    // node_modules and hidden directories are searched when the pattern names them
+    let names: Vec<&str> = pattern.split('/').filter(|v| !is_glob(v)).collect();
+    let mut matched = false;
+    walk_dir(
+        &base,
+        &names,
+        &mut |file| {
+            let relative = normalize(file);
+            if glob_match(pattern, &relative.to_string_lossy().replace('\\', "/")) {
+                files.push(relative);
+                matched = true;
+            }
+        },
+        errors,
+    );
+    if !matched {
+        errors.push(format!("{}: no files matched", path));
+    }
+}
+
+// the path without `.` segments, e.g. `src/a.js` for `./src/a.js`
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+// whether the path has glob characters, so it needs to be matched instead of read
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+// call `cb` with every file in the directory, skipping node_modules and hidden directories
+// unless they're in `names`. entries that can't be read are added to `errors` and skipped
+fn walk_dir<F>(dir: &Path, names: &[&str], cb: &mut F, errors: &mut Vec<String>)
+where
+    F: FnMut(&Path),
+{
+    let mut entries = Vec::new();
+    match fs::read_dir(dir) {
+        Ok(read_dir) => {
+            for entry in read_dir {
+                match entry {
+                    Ok(entry) => entries.push(entry),
+                    Err(err) => errors.push(format!("{}: {}", dir.display(), err)),
+                }
+            }
+        }
+        Err(err) => errors.push(format!("{}: {}", dir.display(), err)),
+    }
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(err) => {
+                errors.push(format!("{}: {}", path.display(), err));
+                continue;
+            }
+        };
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if file_type.is_dir() {
+            let is_skipped = name == "node_modules" || name.starts_with('.');
+            if !is_skipped || names.contains(&name.as_ref()) {
+                walk_dir(&path, names, cb, errors);
+            }
+        } else {
+            cb(&path);
+        }
+    }
+}
+
+fn print_table(results: &[FileResult]) {
+    let width = |f: fn(&FileResult) -> &str, title: &str| {
+        results
+            .iter()
+            .map(|r| f(r).len())
+            .max()
+            .unwrap_or(0)
+            .max(title.len())
+    };
+    let path_width = width(|r| &r.path, "PATH");
+    let syntax_width = width(|r| &r.syntax, "SYNTAX");
+    let format_width = width(|r| &r.format, "FORMAT");
+
+    println!(
+        "{:path_width$}  {:syntax_width$}  {:format_width$}  CONFIDENCE",
+        "PATH", "SYNTAX", "FORMAT"
+    );
+    for r in results {
+        println!(
+            "{:path_width$}  {:syntax_width$}  {:format_width$}  {}",
+            r.path, r.syntax, r.format, r.confidence
+        );
+    }
+}
+
+fn print_hazard_table(results: &[(&str, PackageHazard)]) {
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|(dir, h)| {
+            let files = [("import", &h.import), ("require", &h.require)]
+                .into_iter()
+                .filter_map(|(name, target)| Some(format!("{} {}", name, target.as_ref()?)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            [
+                dir.replace('\\', "/"),
+                h.subpath.clone(),
+                format!("{:?}", h.kind),
+                files,
+            ]
+        })
+        .collect();
+    let width = |i: usize, title: &str| {
+        rows.iter()
+            .map(|row| row[i].len())
+            .max()
+            .unwrap_or(0)
+            .max(title.len())
+    };
+    let package_width = width(0, "PACKAGE");
+    let subpath_width = width(1, "SUBPATH");
+    let kind_width = width(2, "HAZARD");
+
+    println!(
+        "{:package_width$}  {:subpath_width$}  {:kind_width$}  FILES",
+        "PACKAGE", "SUBPATH", "HAZARD"
+    );
+    for [package, subpath, kind, files] in &rows {
+        println!(
+            "{:package_width$}  {:subpath_width$}  {:kind_width$}  {}",
+            package, subpath, kind, files
+        );
+    }
+}
+
+fn hazard_to_json(dir: &str, h: &PackageHazard) -> String {
+    let optional = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+    format!(
+        "{{\"package\":{},\"subpath\":{},\"kind\":\"{:?}\",\"import\":{},\"require\":{},\"format\":{},\"syntax\":{}}}",
+        json_string(&dir.replace('\\', "/")),
+        json_string(&h.subpath),
+        h.kind,
+        optional(h.import.as_deref().map(json_string)),
+        optional(h.require.as_deref().map(json_string)),
+        optional(h.format.as_ref().map(|f| format!("\"{:?}\"", f.format))),
+        optional(h.format.as_ref().map(|f| format!("\"{:?}\"", f.syntax))),
+    )
+}
+
+fn to_json(r: &FileResult) -> String {
+    format!(
+        "{{\"path\":{},\"syntax\":\"{}\",\"format\":\"{}\",\"confidence\":\"{}\",\"ambiguous\":{}}}",
+        json_string(&r.path),
+        r.syntax,
+        r.format,
+        r.confidence,
+        r.ambiguous
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,105 @@
+// variants of the public functions that take utf-8 bytes, e.g. a node `Buffer`, so js doesn't
+// need to decode the file into a string first.
+//
+// valid utf-8 isn't copied. invalid sequences are replaced with the 3 bytes of U+FFFD like
+// `buffer.toString()` does, so byte offsets in spans index the decoded string and shift after
+// an invalid sequence, while utf-16 offsets always match `buffer.toString()`. a byte order mark
+// is kept in the string, but ignored like whitespace.
+
+use crate::{
+    CjsParseResult, CjsRequire, EsmParseResult, JsComment, JsDialect, JsDirective, JsFormat,
+    JsNodeFormat, JsPackageResolution, JsSourceMapComments, JsSyntax, JsSyntaxAnalysis,
+    JsSyntaxGuess, SpecifierRewriter,
+};
+use std::borrow::Cow;
+use wasm_bindgen::prelude::*;
+
+fn decode(b: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(b)
+}
+
+#[wasm_bindgen(js_name = "guessJsSyntaxBytes")]
+pub fn guess_js_syntax_bytes(b: &[u8], dialect: JsDialect) -> JsSyntax {
+    crate::guess_js_syntax_with_dialect(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "guessJsSyntaxWithConfidenceBytes")]
+pub fn guess_js_syntax_with_confidence_bytes(b: &[u8], dialect: JsDialect) -> JsSyntaxGuess {
+    crate::guess_js_syntax_with_confidence(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "guessJsFormatBytes")]
+pub fn guess_js_format_bytes(b: &[u8], dialect: JsDialect) -> JsFormat {
+    crate::guess_js_format_with_dialect(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "analyzeJsSyntaxBytes")]
+pub fn analyze_js_syntax_bytes(b: &[u8], dialect: JsDialect) -> JsSyntaxAnalysis {
+    crate::analyze_js_syntax_with_dialect(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "parseEsmBytes")]
+pub fn parse_esm_bytes(b: &[u8], dialect: JsDialect) -> EsmParseResult {
+    crate::parse_esm(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "parseCjsBytes")]
+pub fn parse_cjs_bytes(b: &[u8], dialect: JsDialect) -> CjsParseResult {
+    crate::parse_cjs(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "parseRequiresBytes")]
+pub fn parse_requires_bytes(b: &[u8], dialect: JsDialect) -> Vec<CjsRequire> {
+    crate::parse_requires(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "parseCommentsBytes")]
+pub fn parse_comments_bytes(b: &[u8], dialect: JsDialect) -> Vec<JsComment> {
+    crate::parse_comments(&decode(b), dialect)
+}
+
+#[wasm_bindgen(js_name = "parseSourceMapCommentsBytes")]
+pub fn parse_source_map_comments_bytes(
+    b: &[u8],
+    dialect: JsDialect,
+    strip: bool,
+) -> JsSourceMapComments {
+    crate::parse_source_map_comments(&decode(b), dialect, strip)
+}
+
+#[wasm_bindgen(js_name = "checkNodeFormatBytes")]
+pub fn check_node_format_bytes(
+    path: &str,
+    b: &[u8],
+    package_json: Option<String>,
+) -> Option<JsNodeFormat> {
+    crate::check_node_format(path, &decode(b), package_json)
+}
+
+#[wasm_bindgen(js_name = "resolvePackageBytes")]
+pub fn resolve_package_bytes(
+    package_json: &[u8],
+    specifier: &str,
+    conditions: Vec<String>,
+) -> JsPackageResolution {
+    crate::resolve_package(&decode(package_json), specifier, conditions)
+}
+
+#[wasm_bindgen(js_name = "directivesBytes")]
+pub fn directives_bytes(b: &[u8]) -> Vec<JsDirective> {
+    crate::directives(&decode(b))
+}
+
+#[wasm_bindgen(js_name = "hashbangBytes")]
+pub fn hashbang_bytes(b: &[u8]) -> Option<String> {
+    crate::hashbang(&decode(b))
+}
+
+#[wasm_bindgen(js_name = "rewriteSpecifiersBytes")]
+pub fn rewrite_specifiers_bytes_js(
+    b: &[u8],
+    dialect: JsDialect,
+    rewriter: &SpecifierRewriter,
+) -> String {
+    crate::rewrite_specifiers_js(&decode(b), dialect, rewriter)
+}
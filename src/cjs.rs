@@ -0,0 +1,303 @@
+use crate::lexer::{skip_expression, static_string_value, Lexer, Token, TokenKind};
+use crate::position::{LineIndex, Span};
+use crate::scope::Scopes;
+use crate::syntax::{scan_js_syntax, JsSyntaxEvidenceKind};
+use crate::walk::WalkCallbackResult;
+use crate::JsDialect;
+use std::cell::OnceCell;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CjsParseResult {
+    // names assigned to `exports` or `module.exports`, in order of appearance
+    pub exports: Vec<String>,
+    // specifiers of modules whose exports are re-exported, e.g. `module.exports = require('foo')`
+    pub reexports: Vec<String>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CjsRequire {
+    // `None` if the argument isn't a string or a template literal without expressions
+    pub specifier: Option<String>,
+    // the specifier without quotes
+    pub specifier_span: Option<Span>,
+    // the whole `require()` call
+    pub span: Span,
+    // whether the argument is an expression, e.g. require(name)
+    pub dynamic: bool,
+}
+
+fn lex_code(s: &str, dialect: JsDialect) -> Vec<Token> {
+    Lexer::with_dialect(s, dialect)
+        .filter(|t| t.kind != TokenKind::Comment)
+        .collect()
+}
+
+pub fn parse_requires(s: &str, dialect: JsDialect) -> Vec<CjsRequire> {
+    let tokens = lex_code(s, dialect);
+    let index = LineIndex::new(s);
+    let mut requires = Vec::new();
+
+    // re-use the esm/cjs detection so shadowed `require` are ignored
+    scan_js_syntax(s, dialect, |kind, start, _| {
+        if kind != JsSyntaxEvidenceKind::Require {
+            return WalkCallbackResult::Continue;
+        }
+        let i = match tokens.binary_search_by_key(&start, |t| t.start) {
+            Ok(i) => i,
+            Err(_) => return WalkCallbackResult::Continue,
+        };
+        let is_punctuator = |i: usize, p: &str| {
+            tokens
+                .get(i)
+                .is_some_and(|t| t.kind == TokenKind::Punctuator && t.text(s) == p)
+        };
+        // foo.require('bar') isn't the commonjs require
+        let is_property = i > 0 && (is_punctuator(i - 1, ".") || is_punctuator(i - 1, "?."));
+        // `require()` without an argument doesn't load anything
+        if is_property || !is_punctuator(i + 1, "(") || is_punctuator(i + 2, ")") {
+            return WalkCallbackResult::Continue;
+        }
+        let close = skip_expression(s, &tokens, i + 2);
+        let end = tokens.get(close).map_or(s.len(), |t| t.end);
+        let specifier = if close == i + 3 {
+            static_string_value(s, &tokens[i + 2])
+        } else {
+            None
+        };
+        requires.push(CjsRequire {
+            dynamic: specifier.is_none(),
+            specifier_span: specifier.as_ref().map(|v| index.span(v.1, v.2)),
+            specifier: specifier.map(|v| v.0),
+            span: index.span(start, end),
+        });
+        WalkCallbackResult::Continue
+    });
+
+    requires
+}
+
+// the re-export helpers that compilers emit
+const REEXPORT_HELPERS: [&str; 2] = ["__exportStar", "__export"];
+
+pub fn parse_cjs(s: &str, dialect: JsDialect) -> CjsParseResult {
+    let tokens = lex_code(s, dialect);
+    let mut parser = CjsParser {
+        s,
+        tokens: &tokens,
+        result: CjsParseResult::default(),
+    };
+
+    // re-use the esm/cjs detection so shadowed `module` and `exports` are ignored
+    let mut references = Vec::new();
+    scan_js_syntax(s, dialect, |kind, start, _| {
+        if let Ok(i) = tokens.binary_search_by_key(&start, |t| t.start) {
+            references.push((i, kind));
+        }
+        WalkCallbackResult::Continue
+    });
+    let mut references = references.into_iter().peekable();
+    // built on the first helper call, to ignore helpers declared in the file
+    let scopes = OnceCell::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if let Some((_, kind)) = references.next_if(|&(index, _)| index == i) {
+            match kind {
+                JsSyntaxEvidenceKind::Module => parser.parse_module(i),
+                JsSyntaxEvidenceKind::Exports => parser.parse_exports(i),
+                _ => {}
+            }
+        }
+
+        // __exportStar(require('foo'), exports) from typescript, __export(require('foo')) from babel
+        // tslib_1.__exportStar(...) with `importHelpers`
+        if REEXPORT_HELPERS
+            .iter()
+            .any(|&name| parser.is_identifier(i, name))
+            && parser.is_punctuator(i + 1, "(")
+            && (i > 0 && parser.is_punctuator(i - 1, ".")
+                || scopes
+                    .get_or_init(|| Scopes::with_names(s, dialect, &REEXPORT_HELPERS))
+                    .is_global_reference(parser.text(i), token.start))
+        {
+            parser.parse_require_reexport(i + 2);
+        }
+    }
+
+    parser.result
+}
+
+struct CjsParser<'a> {
+    s: &'a str,
+    tokens: &'a [Token],
+    result: CjsParseResult,
+}
+
+impl<'a> CjsParser<'a> {
+    fn text(&self, i: usize) -> &'a str {
+        self.tokens.get(i).map_or("", |t| t.text(self.s))
+    }
+
+    fn kind(&self, i: usize) -> Option<TokenKind> {
+        self.tokens.get(i).map(|t| t.kind)
+    }
+
+    fn is_punctuator(&self, i: usize, p: &str) -> bool {
+        self.kind(i) == Some(TokenKind::Punctuator) && self.text(i) == p
+    }
+
+    fn is_identifier(&self, i: usize, name: &str) -> bool {
+        self.kind(i) == Some(TokenKind::Identifier) && self.text(i) == name
+    }
+
+    // the decoded content of a string token
+    fn string(&self, i: usize) -> Option<String> {
+        let token = self.tokens.get(i)?;
+        if token.kind != TokenKind::String {
+            return None;
+        }
+        static_string_value(self.s, token).map(|v| v.0)
+    }
+
+    fn add_export(&mut self, name: String) {
+        if !self.result.exports.contains(&name) {
+            self.result.exports.push(name);
+        }
+    }
+
+    fn add_reexport(&mut self, specifier: String) {
+        if !self.result.reexports.contains(&specifier) {
+            self.result.reexports.push(specifier);
+        }
+    }
+
+    // Object.defineProperty(<target>, 'foo', ...), where `target_start` is the index of `exports`
+    // or `module`
+    fn is_define_property_target(&self, target_start: usize) -> bool {
+        target_start >= 4
+            && self.is_punctuator(target_start - 1, "(")
+            && self.is_identifier(target_start - 2, "defineProperty")
+            && self.is_punctuator(target_start - 3, ".")
+            && self.is_identifier(target_start - 4, "Object")
+    }
+
+    // the object that's exported ends at `i`, check what's assigned to it
+    fn parse_exports_object(&mut self, target_start: usize, i: usize) {
+        // Object.defineProperty(exports, 'foo', ...)
+        if self.is_define_property_target(target_start) && self.is_punctuator(i + 1, ",") {
+            if let Some(name) = self.string(i + 2) {
+                self.add_export(name);
+            }
+            return;
+        }
+
+        // exports.foo = ...
+        if self.is_punctuator(i + 1, ".")
+            && self.kind(i + 2) == Some(TokenKind::Identifier)
+            && self.is_punctuator(i + 3, "=")
+        {
+            self.add_export(self.text(i + 2).to_string());
+            return;
+        }
+
+        // exports['foo'] = ...
+        if self.is_punctuator(i + 1, "[")
+            && self.is_punctuator(i + 3, "]")
+            && self.is_punctuator(i + 4, "=")
+        {
+            if let Some(name) = self.string(i + 2) {
+                self.add_export(name);
+            }
+        }
+    }
+
+    fn parse_exports(&mut self, i: usize) {
+        // foo.exports, module.exports is handled by `parse_module`
+        if i > 0 && (self.is_punctuator(i - 1, ".") || self.is_punctuator(i - 1, "?.")) {
+            return;
+        }
+        self.parse_exports_object(i, i);
+    }
+
+    fn parse_module(&mut self, i: usize) {
+        if !self.is_punctuator(i + 1, ".") || !self.is_identifier(i + 2, "exports") {
+            return;
+        }
+
+        if !self.is_punctuator(i + 3, "=") {
+            self.parse_exports_object(i, i + 2);
+            return;
+        }
+
+        // module.exports = require('foo')
+        if self.parse_require_reexport(i + 4) {
+            return;
+        }
+
+        // module.exports = { foo, bar: baz, 'qux': 1, ...require('foo') }
+        if self.is_punctuator(i + 4, "{") {
+            self.parse_object_literal(i + 5);
+        }
+    }
+
+    // require('foo') at `i`, returns whether it's a re-export
+    fn parse_require_reexport(&mut self, i: usize) -> bool {
+        if self.is_identifier(i, "require")
+            && self.is_punctuator(i + 1, "(")
+            && self.is_punctuator(i + 3, ")")
+        {
+            if let Some(specifier) = self.string(i + 2) {
+                self.add_reexport(specifier);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn parse_object_literal(&mut self, start: usize) {
+        let mut j = start;
+        while let Some(kind) = self.kind(j) {
+            if self.is_punctuator(j, "}") {
+                break;
+            }
+            if self.is_punctuator(j, ",") {
+                j += 1;
+                continue;
+            }
+
+            // ...require('foo')
+            if self.is_punctuator(j, "...") {
+                self.parse_require_reexport(j + 1);
+                j = skip_expression(self.s, self.tokens, j + 1);
+                continue;
+            }
+
+            // getters and setters, get foo() {}
+            if (self.is_identifier(j, "get") || self.is_identifier(j, "set"))
+                && self.kind(j + 1) == Some(TokenKind::Identifier)
+                && self.is_punctuator(j + 2, "(")
+            {
+                j += 1;
+            }
+
+            let name = match kind {
+                TokenKind::Identifier | TokenKind::Number => Some(self.text(j).to_string()),
+                TokenKind::String => self.string(j),
+                _ => None,
+            };
+            if let Some(name) = name {
+                // shorthand, key: value, or method() {}
+                if self.is_punctuator(j + 1, ",")
+                    || self.is_punctuator(j + 1, "}")
+                    || self.is_punctuator(j + 1, ":")
+                    || self.is_punctuator(j + 1, "(")
+                {
+                    self.add_export(name);
+                }
+            }
+            j = skip_expression(self.s, self.tokens, j + 1);
+        }
+    }
+}
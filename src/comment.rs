@@ -0,0 +1,229 @@
+use crate::position::{LineIndex, Span};
+use crate::walk::{WalkItemKind, Walker};
+use crate::JsDialect;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsCommentKind {
+    // `// foo`
+    Line,
+    // `/* foo */`
+    Block,
+    // `#!/usr/bin/env node`
+    Hashbang,
+    // `<!-- foo` or `--> foo`
+    Html,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsCommentAnnotationKind {
+    // `/*#__PURE__*/` or `/*@__PURE__*/`
+    Pure,
+    // `/*#__NO_SIDE_EFFECTS__*/` or `/*@__NO_SIDE_EFFECTS__*/`
+    NoSideEffects,
+    // `/* webpackChunkName: "foo" */`, one for each key
+    Webpack,
+    // `/* @vite-ignore */`
+    ViteIgnore,
+    // `/*! foo */`, `//! foo`, `@license` or `@preserve`, which minifiers keep
+    Legal,
+    // `//# sourceMappingURL=foo.js.map` or `//@ sourceMappingURL=foo.js.map`
+    SourceMappingUrl,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsCommentAnnotation {
+    pub kind: JsCommentAnnotationKind,
+    // the annotation as written, e.g. `#__PURE__`, `webpackChunkName` or `@license`
+    pub name: String,
+    // the raw value, e.g. `"foo"` of `webpackChunkName: "foo"` or the url of a source map
+    pub value: Option<String>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsComment {
+    pub kind: JsCommentKind,
+    // the content without `//`, `/*`, `*/`, `#!`, `<!--` or `-->`
+    pub text: String,
+    pub span: Span,
+    pub annotations: Vec<JsCommentAnnotation>,
+}
+
+pub fn parse_comments(s: &str, dialect: JsDialect) -> Vec<JsComment> {
+    let index = LineIndex::new(s);
+
+    Walker::with_dialect(s, dialect)
+        .filter_map(|item| {
+            let kind = match item.kind {
+                WalkItemKind::LineComment => JsCommentKind::Line,
+                WalkItemKind::BlockComment => JsCommentKind::Block,
+                WalkItemKind::Hashbang => JsCommentKind::Hashbang,
+                WalkItemKind::HtmlComment => JsCommentKind::Html,
+                _ => return None,
+            };
+            // only comments start and end on char boundaries, code items can be single bytes
+            let raw = &s[item.start..item.end];
+            let text = match kind {
+                // unterminated comments don't have */
+                JsCommentKind::Block => raw[2..].strip_suffix("*/").unwrap_or(&raw[2..]),
+                JsCommentKind::Html if raw.starts_with("<!--") => &raw[4..],
+                JsCommentKind::Html => &raw[3..],
+                JsCommentKind::Line | JsCommentKind::Hashbang => &raw[2..],
+            };
+            Some(JsComment {
+                kind,
+                text: text.to_string(),
+                span: index.span(item.start, item.end),
+                annotations: parse_annotations(kind, text),
+            })
+        })
+        .collect()
+}
+
+fn annotation(
+    kind: JsCommentAnnotationKind,
+    name: &str,
+    value: Option<&str>,
+) -> JsCommentAnnotation {
+    JsCommentAnnotation {
+        kind,
+        name: name.to_string(),
+        value: value.map(str::to_string),
+    }
+}
+
+fn parse_annotations(kind: JsCommentKind, text: &str) -> Vec<JsCommentAnnotation> {
+    let mut annotations = Vec::new();
+    if kind == JsCommentKind::Hashbang {
+        return annotations;
+    }
+
+    // #__PURE__ and @__PURE__ mark a call as side-effect free, both are used by bundlers
+    for (kind, name) in [
+        (JsCommentAnnotationKind::Pure, "__PURE__"),
+        (
+            JsCommentAnnotationKind::NoSideEffects,
+            "__NO_SIDE_EFFECTS__",
+        ),
+    ] {
+        if let Some(pos) = text.find(name) {
+            if pos > 0 && matches!(text.as_bytes()[pos - 1], b'#' | b'@') {
+                annotations.push(annotation(kind, &text[pos - 1..pos + name.len()], None));
+            }
+        }
+    }
+
+    if kind == JsCommentKind::Block {
+        annotations.extend(parse_webpack_annotations(text));
+    }
+
+    if text.contains("@vite-ignore") {
+        annotations.push(annotation(
+            JsCommentAnnotationKind::ViteIgnore,
+            "@vite-ignore",
+            None,
+        ));
+    }
+
+    // same rule as esbuild and terser
+    if text.starts_with('!') {
+        annotations.push(annotation(JsCommentAnnotationKind::Legal, "!", None));
+    } else if let Some(name) = ["@license", "@preserve"]
+        .into_iter()
+        .find(|name| text.contains(name))
+    {
+        annotations.push(annotation(JsCommentAnnotationKind::Legal, name, None));
+    }
+
+    if kind == JsCommentKind::Line {
+        if let Some(url) = parse_source_map_comment(text, "sourceMappingURL") {
+            annotations.push(annotation(
+                JsCommentAnnotationKind::SourceMappingUrl,
+                "sourceMappingURL",
+                Some(url),
+            ));
+        }
+    }
+
+    annotations
+}
+
+// the value of `//# name=value` or the legacy `//@ name=value`, `text` is without `//`
+pub fn parse_source_map_comment<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(['#', '@'])?;
+    let rest = rest.strip_prefix([' ', '\t'])?;
+    let value = rest.strip_prefix(name)?.strip_prefix('=')?;
+    // the value ends at whitespace
+    let value = value.split([' ', '\t']).next().unwrap_or("");
+    if value.is_empty() {
+        return None;
+    }
+    Some(value)
+}
+
+// webpack magic comments, e.g. `/* webpackChunkName: "foo", webpackPrefetch: true */`. the
+// values are js expressions, so they're returned as written
+fn parse_webpack_annotations(text: &str) -> Vec<JsCommentAnnotation> {
+    let b = text.as_bytes();
+    let mut annotations = Vec::new();
+    let mut i = 0;
+
+    while let Some(pos) = text[i..].find("webpack") {
+        let start = i + pos;
+        let name_end = b[start..]
+            .iter()
+            .position(|&v| !v.is_ascii_alphanumeric())
+            .map_or(b.len(), |pos| start + pos);
+        i = name_end;
+
+        // webpackChunkName, not webpack or foowebpackBar
+        let is_name = b.get(start + 7).is_some_and(u8::is_ascii_uppercase)
+            && (start == 0 || !b[start - 1].is_ascii_alphanumeric());
+        let colon = text[name_end..].trim_start();
+        if !is_name || !colon.starts_with(':') {
+            continue;
+        }
+
+        let value_start = b.len() - colon.len() + 1;
+        let value_end = find_value_end(b, value_start);
+        let value = text[value_start..value_end].trim();
+        annotations.push(annotation(
+            JsCommentAnnotationKind::Webpack,
+            &text[start..name_end],
+            (!value.is_empty()).then_some(value),
+        ));
+        i = value_end;
+    }
+
+    annotations
+}
+
+// the `,` that ends the value of a webpack magic comment key, skipping strings and brackets
+fn find_value_end(b: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut i = start;
+
+    while i < b.len() {
+        let c = b[i];
+        match quote {
+            Some(_) if c == b'\\' => i += 1,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                b'"' | b'\'' | b'`' => quote = Some(c),
+                b'[' | b'(' | b'{' => depth += 1,
+                b']' | b')' | b'}' => depth -= 1,
+                b',' if depth <= 0 => return i,
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+
+    b.len()
+}
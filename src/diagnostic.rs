@@ -0,0 +1,49 @@
+use crate::position::{LineIndex, Span};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsDiagnosticKind {
+    // a quote without its closing quote before the end of the line
+    UnterminatedString,
+    // a ` without its closing `
+    UnterminatedTemplate,
+    // a /* without its closing */
+    UnterminatedComment,
+    // a regex without its closing / before the end of the file
+    UnterminatedRegex,
+    // a / that was guessed to be a division or a regex, e.g. `(a) / b / c`
+    AmbiguousSlash,
+    // a } without its {, or a { without its } at the end of the file
+    UnbalancedBrace,
+}
+
+impl JsDiagnosticKind {
+    // whether the heuristics had to guess, rather than the source being invalid
+    pub fn is_ambiguity(&self) -> bool {
+        matches!(
+            self,
+            JsDiagnosticKind::AmbiguousSlash | JsDiagnosticKind::UnbalancedBrace
+        )
+    }
+}
+
+// a problem found while walking the source. the walker recovers from it, so results are still
+// returned, but they may be incomplete
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsDiagnostic {
+    pub kind: JsDiagnosticKind,
+    // from the opening quote, comment or slash until where the walker gave up. unclosed braces
+    // are reported at the end of the file
+    pub span: Span,
+}
+
+impl JsDiagnostic {
+    pub fn new(index: &LineIndex, kind: JsDiagnosticKind, start: usize, end: usize) -> Self {
+        JsDiagnostic {
+            kind,
+            span: index.span(start, end),
+        }
+    }
+}
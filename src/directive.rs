@@ -0,0 +1,101 @@
+use crate::position::{LineIndex, Span};
+use crate::walk::{WalkItemKind, Walker};
+use crate::JsDialect;
+use wasm_bindgen::prelude::*;
+
+// a directive in the prologue, e.g. `'use strict'`
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsDirective {
+    // the raw content without quotes, escapes aren't decoded as `'use\x20strict'` isn't strict
+    pub name: String,
+    // the string literal with quotes
+    pub span: Span,
+}
+
+// the hashbang without `#!`, e.g. `/usr/bin/env node`
+pub fn parse_hashbang(s: &str) -> Option<String> {
+    let item =
+        Walker::with_dialect(s, JsDialect::JS).find(|item| item.kind != WalkItemKind::Bom)?;
+    if item.kind != WalkItemKind::Hashbang {
+        return None;
+    }
+    Some(s[item.start + 2..item.end].to_string())
+}
+
+// bytes that continue an expression on the next line, so the string before isn't a directive,
+// e.g. `'use strict'\n.length`
+fn is_expression_continuation(v: u8) -> bool {
+    b".[(`+-*/%,?=<>&|^".contains(&v)
+}
+
+// read the directive prologue, the string literal statements at the start of the file
+pub fn parse_directives(s: &str) -> Vec<JsDirective> {
+    let b = s.as_bytes();
+    let index = LineIndex::new(s);
+    let walker = Walker::with_dialect(s, JsDialect::JS);
+    let mut directives = Vec::new();
+    // the string that may be a directive, until what comes after it is known
+    let mut pending: Option<(usize, usize)> = None;
+    let mut is_new_line = false;
+
+    for item in walker {
+        let c = b[item.start];
+        match item.kind {
+            WalkItemKind::Bom => continue,
+            WalkItemKind::Hashbang
+            | WalkItemKind::LineComment
+            | WalkItemKind::BlockComment
+            | WalkItemKind::HtmlComment => {
+                is_new_line |= s[item.start..item.end].contains('\n');
+                continue;
+            }
+            WalkItemKind::Code if c.is_ascii_whitespace() => {
+                is_new_line |= c == b'\n' || c == b'\r';
+                continue;
+            }
+            _ => {}
+        }
+
+        if let Some((start, end)) = pending.take() {
+            let is_statement_end = match item.kind {
+                WalkItemKind::Code if c == b';' => true,
+                WalkItemKind::Code => is_new_line && !is_expression_continuation(c),
+                // `'a'\n'b'` are two statements
+                WalkItemKind::String => is_new_line,
+                _ => false,
+            };
+            if !is_statement_end {
+                return directives;
+            }
+            directives.push(JsDirective {
+                name: s[start + 1..end - 1].to_string(),
+                span: index.span(start, end),
+            });
+            if c == b';' {
+                continue;
+            }
+        }
+
+        // the last quote closes the string unless it's escaped, e.g. in `'a\'` at the end
+        let escapes = match b.get(item.start + 1..item.end.saturating_sub(1)) {
+            Some(content) => content.iter().rev().take_while(|&&v| v == b'\\').count(),
+            None => 0,
+        };
+        let is_closed = item.end - item.start >= 2 && b[item.end - 1] == c && escapes % 2 == 0;
+        if item.kind != WalkItemKind::String || !is_closed {
+            return directives;
+        }
+        pending = Some((item.start, item.end));
+        is_new_line = false;
+    }
+
+    // the file ends after the string
+    if let Some((start, end)) = pending {
+        directives.push(JsDirective {
+            name: s[start + 1..end - 1].to_string(),
+            span: index.span(start, end),
+        });
+    }
+    directives
+}
@@ -0,0 +1,372 @@
+use crate::lexer::{collect_declaration_names, static_string_value, Lexer, Token, TokenKind};
+use crate::position::{LineIndex, Span};
+use crate::JsDialect;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EsmImportKind {
+    // import foo from 'foo', import 'foo', export { foo } from 'foo'
+    Static,
+    // import('foo')
+    Dynamic,
+    // import.meta
+    Meta,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsmImport {
+    pub kind: EsmImportKind,
+    // `None` for import.meta and dynamic imports that aren't plain strings
+    pub specifier: Option<String>,
+    // the specifier without quotes
+    pub specifier_span: Option<Span>,
+    // the whole statement or expression
+    pub span: Span,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EsmExportKind {
+    // export const foo, export { foo }, export { foo } from 'foo', export * as foo from 'foo'
+    Named,
+    // export default foo
+    Default,
+    // export * from 'foo'
+    Star,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsmExport {
+    pub kind: EsmExportKind,
+    // the exported name, `None` for star exports
+    pub name: Option<String>,
+    // the local binding name, if different from the exported name or re-exported
+    pub local: Option<String>,
+    // the re-exported module, e.g. `export { foo } from 'foo'`
+    pub specifier: Option<String>,
+    // the exported name, or `*` for star exports
+    pub span: Span,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EsmParseResult {
+    pub imports: Vec<EsmImport>,
+    pub exports: Vec<EsmExport>,
+}
+
+pub fn parse_esm(s: &str, dialect: JsDialect) -> EsmParseResult {
+    let tokens: Vec<Token> = Lexer::with_dialect(s, dialect)
+        .filter(|t| t.kind != TokenKind::Comment)
+        .collect();
+    let mut parser = EsmParser {
+        s,
+        index: LineIndex::new(s),
+        tokens: &tokens,
+        result: EsmParseResult::default(),
+    };
+
+    let mut i = 0;
+    while i < tokens.len() {
+        i = match parser.text(i) {
+            "import" if !parser.is_property(i) => parser.parse_import(i),
+            "export" if !parser.is_property(i) => parser.parse_export(i),
+            _ => i + 1,
+        };
+    }
+
+    parser.result
+}
+
+struct EsmParser<'a> {
+    s: &'a str,
+    index: LineIndex<'a>,
+    tokens: &'a [Token],
+    result: EsmParseResult,
+}
+
+impl<'a> EsmParser<'a> {
+    // token text, or an empty string if out of bounds
+    fn text(&self, i: usize) -> &'a str {
+        self.tokens.get(i).map_or("", |t| t.text(self.s))
+    }
+
+    fn kind(&self, i: usize) -> Option<TokenKind> {
+        self.tokens.get(i).map(|t| t.kind)
+    }
+
+    fn is_punctuator(&self, i: usize, p: &str) -> bool {
+        self.kind(i) == Some(TokenKind::Punctuator) && self.text(i) == p
+    }
+
+    fn is_identifier(&self, i: usize, name: &str) -> bool {
+        self.kind(i) == Some(TokenKind::Identifier) && self.text(i) == name
+    }
+
+    fn is_string(&self, i: usize) -> bool {
+        self.kind(i) == Some(TokenKind::String)
+    }
+
+    // foo.import, foo?.export, { import: 1 }
+    fn is_property(&self, i: usize) -> bool {
+        (i > 0 && (self.is_punctuator(i - 1, ".") || self.is_punctuator(i - 1, "?.")))
+            || self.is_punctuator(i + 1, ":")
+    }
+
+    fn static_string(&self, i: usize) -> Option<(String, usize, usize)> {
+        static_string_value(self.s, self.tokens.get(i)?)
+    }
+
+    fn push_import(
+        &mut self,
+        kind: EsmImportKind,
+        specifier_index: Option<usize>,
+        start: usize,
+        end: usize,
+    ) {
+        let specifier = specifier_index.and_then(|i| self.static_string(i));
+        self.result.imports.push(EsmImport {
+            kind,
+            specifier_span: specifier.as_ref().map(|v| self.index.span(v.1, v.2)),
+            specifier: specifier.map(|v| v.0),
+            span: self.index.span(start, end),
+        });
+    }
+
+    fn push_static_import(&mut self, specifier_index: usize, start: usize, end: usize) {
+        self.push_import(EsmImportKind::Static, Some(specifier_index), start, end);
+    }
+
+    // skip `with { type: 'json' }` or `assert { type: 'json' }` after a specifier, returns the
+    // index after the statement
+    fn skip_import_attributes(&self, i: usize) -> usize {
+        if (self.is_identifier(i, "with") || self.is_identifier(i, "assert"))
+            && self.is_punctuator(i + 1, "{")
+        {
+            if let Some(close) = self.find_closing(i + 1) {
+                return close + 1;
+            }
+        }
+        i
+    }
+
+    // find the matching closing bracket of the opening bracket at `i`
+    fn find_closing(&self, i: usize) -> Option<usize> {
+        let mut depth = 0;
+        for j in i..self.tokens.len() {
+            if self.kind(j) != Some(TokenKind::Punctuator) {
+                continue;
+            }
+            match self.text(j) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(j);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn end_of(&self, i: usize) -> usize {
+        self.tokens.get(i).map_or(self.s.len(), |t| t.end)
+    }
+
+    fn parse_import(&mut self, i: usize) -> usize {
+        let start = self.tokens[i].start;
+
+        // import.meta
+        if self.is_punctuator(i + 1, ".") && self.is_identifier(i + 2, "meta") {
+            self.push_import(EsmImportKind::Meta, None, start, self.tokens[i + 2].end);
+            return i + 3;
+        }
+
+        // import('foo')
+        if self.is_punctuator(i + 1, "(") {
+            let close = self.find_closing(i + 1);
+            let is_plain = matches!(close, Some(close) if close == i + 3)
+                || (self.is_punctuator(i + 3, ",") && close.is_some());
+            let end = close.map_or(self.s.len(), |close| self.tokens[close].end);
+            let specifier_index = if is_plain { Some(i + 2) } else { None };
+            self.push_import(EsmImportKind::Dynamic, specifier_index, start, end);
+            // continue inside the parens so nested imports are found too
+            return i + 2;
+        }
+
+        // import 'foo'
+        if self.is_string(i + 1) {
+            let next = self.skip_import_attributes(i + 2);
+            self.push_static_import(i + 1, start, self.end_of(next - 1));
+            return next;
+        }
+
+        // import foo, { bar as baz }, * as qux from 'foo'
+        let mut j = i + 1;
+        while let Some(kind) = self.kind(j) {
+            let is_clause = match kind {
+                TokenKind::Identifier => true,
+                TokenKind::String => true,
+                TokenKind::Punctuator => matches!(self.text(j), "{" | "}" | "," | "*"),
+                _ => false,
+            };
+            if !is_clause {
+                break;
+            }
+            if self.is_identifier(j, "from") && self.is_string(j + 1) {
+                let next = self.skip_import_attributes(j + 2);
+                self.push_static_import(j + 1, start, self.end_of(next - 1));
+                return next;
+            }
+            j += 1;
+        }
+
+        i + 1
+    }
+
+    fn push_export(&mut self, kind: EsmExportKind, name_index: usize, local: Option<String>) {
+        let token = self.tokens[name_index];
+        let name = match kind {
+            EsmExportKind::Star => None,
+            _ => Some(self.export_name(name_index)),
+        };
+        self.result.exports.push(EsmExport {
+            kind,
+            name,
+            local,
+            specifier: None,
+            span: self.index.span(token.start, token.end),
+        });
+    }
+
+    // export names can be identifiers or strings, e.g. export { foo as 'foo bar' }
+    fn export_name(&self, i: usize) -> String {
+        match self.static_string(i) {
+            Some((name, _, _)) => name,
+            None => self.text(i).to_string(),
+        }
+    }
+
+    fn parse_export(&mut self, i: usize) -> usize {
+        let next = i + 1;
+
+        match self.text(next) {
+            "default" => {
+                self.push_export(EsmExportKind::Default, next, None);
+                next + 1
+            }
+            "var" | "let" | "const" => self.parse_export_declarations(next + 1),
+            "async" if self.is_identifier(next + 1, "function") => {
+                self.parse_export_function(next + 1)
+            }
+            "function" => self.parse_export_function(next),
+            "class" => {
+                if self.kind(next + 1) == Some(TokenKind::Identifier) {
+                    self.push_export(EsmExportKind::Named, next + 1, None);
+                }
+                next + 1
+            }
+            "{" => self.parse_export_specifiers(i, next),
+            "*" => {
+                let start = self.tokens[i].start;
+                // export * as foo from 'foo'
+                let (kind, name_index, from) = if self.is_identifier(next + 1, "as") {
+                    (EsmExportKind::Named, next + 2, next + 3)
+                } else {
+                    (EsmExportKind::Star, next, next + 1)
+                };
+                if !self.is_identifier(from, "from") || !self.is_string(from + 1) {
+                    return next;
+                }
+                self.push_export(kind, name_index, None);
+                self.set_reexport(1, from + 1);
+                let end = self.skip_import_attributes(from + 2);
+                self.push_static_import(from + 1, start, self.end_of(end - 1));
+                end
+            }
+            _ => next,
+        }
+    }
+
+    // set the specifier for the last `count` exports
+    fn set_reexport(&mut self, count: usize, specifier_index: usize) {
+        let specifier = self.static_string(specifier_index).map(|v| v.0);
+        let len = self.result.exports.len();
+        for export in &mut self.result.exports[len - count..] {
+            export.specifier = specifier.clone();
+            if export.local.is_none() && export.kind == EsmExportKind::Named {
+                export.local = Some("*".to_string());
+            }
+        }
+    }
+
+    fn parse_export_function(&mut self, i: usize) -> usize {
+        let mut j = i + 1;
+        // generator
+        if self.is_punctuator(j, "*") {
+            j += 1;
+        }
+        if self.kind(j) == Some(TokenKind::Identifier) {
+            self.push_export(EsmExportKind::Named, j, None);
+        }
+        j
+    }
+
+    // export const foo = 1, { bar, baz: [qux] } = obj
+    fn parse_export_declarations(&mut self, i: usize) -> usize {
+        let mut names = Vec::new();
+        let end = collect_declaration_names(self.s, self.tokens, i, &mut names);
+        for name_index in names {
+            self.push_export(EsmExportKind::Named, name_index, None);
+        }
+        end
+    }
+
+    // export { foo, bar as baz } or export { foo } from 'foo'
+    fn parse_export_specifiers(&mut self, export_index: usize, open: usize) -> usize {
+        let close = match self.find_closing(open) {
+            Some(close) => close,
+            None => return open + 1,
+        };
+
+        let mut count = 0;
+        let mut j = open + 1;
+        while j < close {
+            if self.is_punctuator(j, ",") {
+                j += 1;
+                continue;
+            }
+            if self.is_identifier(j + 1, "as") && j + 2 < close {
+                let local = self.export_name(j);
+                self.push_export(EsmExportKind::Named, j + 2, Some(local));
+                j += 3;
+            } else {
+                self.push_export(EsmExportKind::Named, j, None);
+                j += 1;
+            }
+            count += 1;
+        }
+
+        if self.is_identifier(close + 1, "from") && self.is_string(close + 2) {
+            // the local name is the name from the re-exported module
+            let len = self.result.exports.len();
+            for export in &mut self.result.exports[len - count..] {
+                if export.local.is_none() {
+                    export.local = export.name.clone();
+                }
+            }
+            self.set_reexport(count, close + 2);
+            let end = self.skip_import_attributes(close + 3);
+            let start = self.tokens[export_index].start;
+            self.push_static_import(close + 2, start, self.end_of(end - 1));
+            return end;
+        }
+
+        close + 1
+    }
+}
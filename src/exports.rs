@@ -0,0 +1,332 @@
+use crate::json::JsonValue;
+use std::cmp::Ordering;
+use wasm_bindgen::prelude::*;
+
+// why a specifier couldn't be resolved, named after the node error codes
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsResolveError {
+    // `ERR_INVALID_PACKAGE_CONFIG`, e.g. `exports` mixes subpaths and conditions
+    InvalidPackageConfig,
+    // `ERR_INVALID_PACKAGE_TARGET`, e.g. a target that doesn't start with `./`
+    InvalidPackageTarget,
+    // `ERR_INVALID_MODULE_SPECIFIER`, e.g. `#` or a `*` match with `..`
+    InvalidModuleSpecifier,
+    // `ERR_PACKAGE_PATH_NOT_EXPORTED`, including subpaths with a `null` target
+    PackagePathNotExported,
+    // `ERR_PACKAGE_IMPORT_NOT_DEFINED`
+    PackageImportNotDefined,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsPackageResolution {
+    // the file relative to the package, e.g. `./dist/index.mjs`, or a bare specifier for
+    // `imports` that map to another package
+    pub target: Option<String>,
+    pub error: Option<JsResolveError>,
+}
+
+impl From<Result<String, JsResolveError>> for JsPackageResolution {
+    fn from(result: Result<String, JsResolveError>) -> Self {
+        match result {
+            Ok(target) => JsPackageResolution {
+                target: Some(target),
+                error: None,
+            },
+            Err(error) => JsPackageResolution {
+                target: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+// the result of resolving a target, node distinguishes `null` targets, which exclude a subpath,
+// from conditions that didn't match, which fall through to the next one
+enum Resolved {
+    Target(String),
+    Null,
+    Undefined,
+}
+
+// resolve `pkg`, `pkg/sub/path` or `#internal` against the package.json of `pkg`, like node
+// does for the given conditions, e.g. `["node", "import"]`. `default` always matches. relative
+// and absolute specifiers aren't package specifiers, use `resolve_package_exports` for subpaths
+pub fn resolve_package_specifier(
+    package_json: &JsonValue,
+    specifier: &str,
+    conditions: &[&str],
+) -> Result<String, JsResolveError> {
+    if specifier.starts_with('#') {
+        return resolve_package_imports(package_json, specifier, conditions);
+    }
+    // the name is the first segment, or the first two for scoped packages
+    let scoped = specifier.starts_with('@');
+    let name_len = specifier
+        .match_indices('/')
+        .nth(usize::from(scoped))
+        .map_or(specifier.len(), |(i, _)| i);
+    let name = &specifier[..name_len];
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['\\', '%'])
+        || (scoped && (!name.contains('/') || name.starts_with("@/") || name.ends_with('/')))
+    {
+        return Err(JsResolveError::InvalidModuleSpecifier);
+    }
+    let subpath = format!(".{}", &specifier[name_len..]);
+    resolve_package_exports(package_json, &subpath, conditions)
+}
+
+// resolve a subpath of the package, `.` or `./sub/path`, with its `exports`. without `exports`
+// every subpath is exported as is and `.` is `main` or `./index.js`, but unlike node, no
+// extensions or directory indexes are tried
+pub fn resolve_package_exports(
+    package_json: &JsonValue,
+    subpath: &str,
+    conditions: &[&str],
+) -> Result<String, JsResolveError> {
+    if subpath != "." && !subpath.starts_with("./") {
+        return Err(JsResolveError::InvalidModuleSpecifier);
+    }
+    let exports = match package_json.get("exports") {
+        None | Some(JsonValue::Null) => return Ok(legacy_resolve(package_json, subpath)),
+        Some(exports) => exports,
+    };
+
+    let subpath_keys = match exports {
+        JsonValue::Object(entries) => {
+            let dots = entries.iter().filter(|(k, _)| k.starts_with('.')).count();
+            if dots > 0 && dots < entries.len() {
+                return Err(JsResolveError::InvalidPackageConfig);
+            }
+            dots > 0
+        }
+        _ => false,
+    };
+
+    let resolved = if subpath == "." {
+        let main = if subpath_keys {
+            exports.get(".")
+        } else {
+            Some(exports)
+        };
+        match main {
+            Some(main) => resolve_target(main, None, false, conditions)?,
+            None => Resolved::Undefined,
+        }
+    } else if subpath_keys {
+        resolve_match(subpath, exports, false, conditions)?
+    } else {
+        Resolved::Undefined
+    };
+
+    match resolved {
+        Resolved::Target(target) => Ok(target),
+        _ => Err(JsResolveError::PackagePathNotExported),
+    }
+}
+
+// resolve a `#internal` specifier with the `imports` of the package
+pub fn resolve_package_imports(
+    package_json: &JsonValue,
+    specifier: &str,
+    conditions: &[&str],
+) -> Result<String, JsResolveError> {
+    if !specifier.starts_with('#')
+        || specifier == "#"
+        || specifier.starts_with("#/")
+        || specifier.ends_with('/')
+    {
+        return Err(JsResolveError::InvalidModuleSpecifier);
+    }
+    match package_json.get("imports") {
+        Some(imports @ JsonValue::Object(_)) => {
+            match resolve_match(specifier, imports, true, conditions)? {
+                Resolved::Target(target) => Ok(target),
+                _ => Err(JsResolveError::PackageImportNotDefined),
+            }
+        }
+        _ => Err(JsResolveError::PackageImportNotDefined),
+    }
+}
+
+fn legacy_resolve(package_json: &JsonValue, subpath: &str) -> String {
+    if subpath != "." {
+        return subpath.to_string();
+    }
+    match package_json.get("main").and_then(JsonValue::as_str) {
+        Some(main) if !main.is_empty() => {
+            format!("./{}", main.strip_prefix("./").unwrap_or(main))
+        }
+        _ => "./index.js".to_string(),
+    }
+}
+
+// find the key in `exports` or `imports` that matches, an exact key or the most specific `*`
+// pattern
+fn resolve_match(
+    key: &str,
+    map: &JsonValue,
+    is_imports: bool,
+    conditions: &[&str],
+) -> Result<Resolved, JsResolveError> {
+    let JsonValue::Object(entries) = map else {
+        return Ok(Resolved::Undefined);
+    };
+    if !key.contains('*') {
+        if let Some(target) = map.get(key) {
+            return resolve_target(target, None, is_imports, conditions);
+        }
+    }
+
+    let mut patterns: Vec<&str> = entries
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .filter(|k| k.matches('*').count() == 1)
+        .collect();
+    patterns.sort_by(|a, b| pattern_key_compare(a, b));
+    for pattern in patterns {
+        let (base, trailer) = pattern.split_once('*').unwrap_or((pattern, ""));
+        if key.starts_with(base)
+            && key != base
+            && (trailer.is_empty() || (key.ends_with(trailer) && key.len() >= pattern.len()))
+        {
+            let pattern_match = &key[base.len()..key.len() - trailer.len()];
+            let target = map.get(pattern).unwrap_or(&JsonValue::Null);
+            return resolve_target(target, Some(pattern_match), is_imports, conditions);
+        }
+    }
+    Ok(Resolved::Undefined)
+}
+
+// longer prefixes before the `*` first, then longer keys
+fn pattern_key_compare(a: &str, b: &str) -> Ordering {
+    let base_len = |k: &str| k.find('*').map_or(k.len(), |i| i + 1);
+    base_len(b)
+        .cmp(&base_len(a))
+        .then_with(|| match (a.contains('*'), b.contains('*')) {
+            (false, _) => Ordering::Greater,
+            (_, false) => Ordering::Less,
+            _ => b.len().cmp(&a.len()),
+        })
+}
+
+fn resolve_target(
+    target: &JsonValue,
+    pattern_match: Option<&str>,
+    is_imports: bool,
+    conditions: &[&str],
+) -> Result<Resolved, JsResolveError> {
+    match target {
+        JsonValue::String(target) => resolve_string_target(target, pattern_match, is_imports),
+        JsonValue::Object(entries) => {
+            if entries.iter().any(|(k, _)| is_array_index(k)) {
+                return Err(JsResolveError::InvalidPackageConfig);
+            }
+            for (condition, value) in entries {
+                if condition == "default" || conditions.contains(&condition.as_str()) {
+                    match resolve_target(value, pattern_match, is_imports, conditions)? {
+                        Resolved::Undefined => continue,
+                        resolved => return Ok(resolved),
+                    }
+                }
+            }
+            Ok(Resolved::Undefined)
+        }
+        JsonValue::Array(targets) => {
+            if targets.is_empty() {
+                return Ok(Resolved::Null);
+            }
+            // fall back to the next target if one is invalid, e.g. for newer node features
+            let mut last = Ok(Resolved::Undefined);
+            for target in targets {
+                match resolve_target(target, pattern_match, is_imports, conditions) {
+                    Err(JsResolveError::InvalidPackageTarget) => {
+                        last = Err(JsResolveError::InvalidPackageTarget)
+                    }
+                    Err(err) => return Err(err),
+                    Ok(Resolved::Undefined) => {}
+                    Ok(Resolved::Null) => last = Ok(Resolved::Null),
+                    Ok(resolved) => return Ok(resolved),
+                }
+            }
+            last
+        }
+        JsonValue::Null => Ok(Resolved::Null),
+        _ => Err(JsResolveError::InvalidPackageTarget),
+    }
+}
+
+fn resolve_string_target(
+    target: &str,
+    pattern_match: Option<&str>,
+    is_imports: bool,
+) -> Result<Resolved, JsResolveError> {
+    let replace = |target: &str| match pattern_match {
+        Some(pattern_match) => target.replace('*', pattern_match),
+        None => target.to_string(),
+    };
+
+    let Some(rest) = target.strip_prefix("./") else {
+        // `imports` can map to other packages
+        if is_imports && !target.starts_with("../") && !target.starts_with('/') && !is_url(target) {
+            return Ok(Resolved::Target(replace(target)));
+        }
+        return Err(JsResolveError::InvalidPackageTarget);
+    };
+    if rest.split(['/', '\\']).any(is_invalid_segment) {
+        return Err(JsResolveError::InvalidPackageTarget);
+    }
+    if let Some(pattern_match) = pattern_match {
+        if pattern_match.split(['/', '\\']).any(is_invalid_segment) {
+            return Err(JsResolveError::InvalidModuleSpecifier);
+        }
+    }
+    Ok(Resolved::Target(replace(target)))
+}
+
+// segments that could escape the package or reach into its dependencies, also when they're
+// percent encoded
+fn is_invalid_segment(segment: &str) -> bool {
+    let mut decoded = String::with_capacity(segment.len());
+    let mut rest = segment;
+    while let Some(c) = rest.chars().next() {
+        let hex = rest
+            .strip_prefix('%')
+            .and_then(|v| v.get(..2))
+            .and_then(|v| u8::from_str_radix(v, 16).ok())
+            .filter(u8::is_ascii);
+        match hex {
+            Some(v) => {
+                decoded.push(v as char);
+                rest = &rest[3..];
+            }
+            None => {
+                decoded.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    decoded.make_ascii_lowercase();
+    matches!(decoded.as_str(), "." | ".." | "node_modules")
+}
+
+// e.g. `node:fs` or `https://example.com`
+fn is_url(target: &str) -> bool {
+    match target.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+fn is_array_index(key: &str) -> bool {
+    key.parse::<u32>()
+        .is_ok_and(|v| v != u32::MAX && v.to_string() == key)
+}
@@ -0,0 +1,136 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::syntax::{scan_js_syntax, JsSyntaxEvidenceKind};
+use crate::walk::WalkCallbackResult;
+use crate::{JsDialect, JsSyntax};
+use wasm_bindgen::prelude::*;
+
+// the module format of the file, which is more specific than `JsSyntax` for scripts that wrap
+// themselves, e.g. umd bundles that work as both amd and cjs
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsFormat {
+    ESM,
+    CJS,
+    // `define.amd` together with `module` or `exports`
+    UMD,
+    // `define([...], factory)`
+    AMD,
+    // `System.register([...], factory)`
+    SystemJS,
+    // a script that starts with `(function () {})()`, which usually assigns globals
+    IIFE,
+    Mixed,
+    Unknown,
+}
+
+// collects which kinds of evidence were found to decide the format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsFormatFlags {
+    is_esm: bool,
+    is_cjs: bool,
+    is_amd: bool,
+    is_system: bool,
+}
+
+impl JsFormatFlags {
+    pub fn add(&mut self, kind: JsSyntaxEvidenceKind) {
+        match kind {
+            JsSyntaxEvidenceKind::AmdDefine => self.is_amd = true,
+            JsSyntaxEvidenceKind::SystemRegister => self.is_system = true,
+            _ => match kind.syntax() {
+                JsSyntax::ESM => self.is_esm = true,
+                JsSyntax::CJS => self.is_cjs = true,
+                _ => {}
+            },
+        }
+    }
+
+    pub fn format(&self, s: &str, dialect: JsDialect) -> JsFormat {
+        if self.is_esm {
+            if self.is_cjs || self.is_amd || self.is_system {
+                return JsFormat::Mixed;
+            }
+            return JsFormat::ESM;
+        }
+        // the cjs and amd references of a system module are within its factory
+        if self.is_system {
+            return JsFormat::SystemJS;
+        }
+        if self.is_amd {
+            if self.is_cjs {
+                return JsFormat::UMD;
+            }
+            return JsFormat::AMD;
+        }
+        if self.is_cjs {
+            return JsFormat::CJS;
+        }
+        if is_iife(s, dialect) {
+            return JsFormat::IIFE;
+        }
+        JsFormat::Unknown
+    }
+}
+
+pub fn guess_js_format(s: &str, dialect: JsDialect) -> JsFormat {
+    let mut flags = JsFormatFlags::default();
+    scan_js_syntax(s, dialect, |kind, _, _| {
+        flags.add(kind);
+        WalkCallbackResult::Continue
+    });
+    flags.format(s, dialect)
+}
+
+// whether the first statement, after directives, is an immediately invoked function, e.g.
+// `(function () {})()`, `!function () {}()`, `(() => {})()` or `var Foo = (function () {})()`
+fn is_iife(s: &str, dialect: JsDialect) -> bool {
+    let tokens: Vec<Token> = Lexer::with_dialect(s, dialect)
+        .filter(|t| t.kind != TokenKind::Comment)
+        .take(64)
+        .collect();
+    let text = |i: usize| tokens.get(i).map_or("", |t| t.text(s));
+
+    // 'use strict';
+    let mut i = 0;
+    while i < tokens.len() && (tokens[i].kind == TokenKind::String || text(i) == ";") {
+        i += 1;
+    }
+
+    // var Foo = (function () {})()
+    if matches!(text(i), "var" | "let" | "const") && text(i + 2) == "=" {
+        i += 3;
+    }
+
+    match text(i) {
+        "!" | "+" | "-" | "~" | "void" => text(i + 1) == "function",
+        "(" => {
+            let mut j = i + 1;
+            if text(j) == "async" {
+                j += 1;
+            }
+            if text(j) == "function" {
+                return true;
+            }
+            // (() => {})() or ((global) => {})(this)
+            if text(j) != "(" {
+                return false;
+            }
+            let mut depth = 0;
+            while j < tokens.len() {
+                match text(j) {
+                    "(" => depth += 1,
+                    ")" => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return text(j + 1) == "=>";
+                        }
+                    }
+                    _ => {}
+                }
+                j += 1;
+            }
+            false
+        }
+        _ => false,
+    }
+}
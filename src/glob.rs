@@ -0,0 +1,52 @@
+// match a `/` separated path against a glob pattern, e.g. `src/**/*.js`. `**` matches any number
+// of directories, `*` anything but `/` and `?` a single character
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<Vec<char>> = pattern
+        .split('/')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.chars().collect())
+        .collect();
+    let path: Vec<Vec<char>> = path
+        .split('/')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.chars().collect())
+        .collect();
+    wildcard_match(
+        &pattern,
+        &path,
+        |segment| segment[..] == ['*', '*'],
+        |pattern, name| wildcard_match(pattern, name, |&c| c == '*', |&p, &c| p == '?' || p == c),
+    )
+}
+
+// match with wildcards that stand for any number of items, `**` for segments and `*` for
+// characters. only the last wildcard is backtracked to, because a later wildcard can match
+// anything an earlier one could, so it's at most `pattern.len() * input.len()` steps instead of
+// exponential for patterns like `*a*a*a*b`
+fn wildcard_match<T>(
+    pattern: &[T],
+    input: &[T],
+    is_wildcard: impl Fn(&T) -> bool,
+    is_match: impl Fn(&T, &T) -> bool,
+) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // the pattern index after the last wildcard, and the input index it matches up to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while i < input.len() {
+        if p < pattern.len() && is_wildcard(&pattern[p]) {
+            p += 1;
+            backtrack = Some((p, i));
+        } else if p < pattern.len() && is_match(&pattern[p], &input[i]) {
+            p += 1;
+            i += 1;
+        } else if let Some((wildcard_end, matched)) = backtrack {
+            // let the wildcard match one more item
+            p = wildcard_end;
+            i = matched + 1;
+            backtrack = Some((wildcard_end, i));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_wildcard)
+}
@@ -0,0 +1,206 @@
+use crate::esm::parse_esm;
+use crate::exports::resolve_package_exports;
+use crate::json::{parse_json, JsonValue};
+use crate::node::check_node_format;
+use crate::{JsDialect, JsNodeFormat, JsSyntax};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageHazardKind {
+    // `import` and `require` resolve to different files, so the package can be loaded twice with
+    // separate state, e.g. `instanceof` checks fail across the two copies
+    DualPackage,
+    // the `require` target is esm, which `require` can't load in older node versions
+    RequireEsm,
+    // the `import` target is cjs, so named imports may not be found
+    ImportCjs,
+    // the target doesn't exist
+    MissingFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageHazard {
+    pub kind: PackageHazardKind,
+    // the subpath of `exports`, e.g. `.` or `./utils`
+    pub subpath: String,
+    // the targets the finding is about, both for `DualPackage`
+    pub import: Option<String>,
+    pub require: Option<String>,
+    // how node loads the target and what its code looks like, for `RequireEsm` and `ImportCjs`
+    pub format: Option<JsNodeFormat>,
+}
+
+// the `import` and `require` conditions node resolves `exports` with
+const IMPORT: [&str; 2] = ["node", "import"];
+const REQUIRE: [&str; 2] = ["node", "require"];
+
+// check the `exports` of a package for targets that can be loaded twice or in the wrong format.
+// `read` returns the contents of a file relative to the package, e.g. `./dist/index.mjs`, or
+// `None` if it doesn't exist. subpath patterns are skipped, and the format isn't checked when
+// `import` and `require` both resolve to the `default` target, so packages that only ship esm or
+// cjs aren't reported. returns `None` if the package.json is invalid
+pub fn check_package_hazards<F>(package_json: &str, mut read: F) -> Option<Vec<PackageHazard>>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let json = parse_json(package_json)?;
+    let mut hazards = Vec::new();
+
+    for subpath in export_subpaths(&json) {
+        let resolve = |conditions: &[&str]| resolve_package_exports(&json, &subpath, conditions);
+        let fallback = resolve(&["node"]).ok();
+        let import = resolve(&IMPORT).ok();
+        let require = resolve(&REQUIRE).ok();
+        let single_target = import == require && require == fallback;
+        let hazard =
+            |kind, import: Option<&String>, require: Option<&String>, format| PackageHazard {
+                kind,
+                subpath: subpath.clone(),
+                import: import.cloned(),
+                require: require.cloned(),
+                format,
+            };
+
+        let import_s = import.as_ref().and_then(|target| read(target));
+        let require_s = match (&import, &require) {
+            (Some(import), Some(require)) if import == require => import_s.clone(),
+            (_, require) => require.as_ref().and_then(|target| read(target)),
+        };
+
+        if import.is_some() && import_s.is_none() {
+            let same = import == require;
+            hazards.push(hazard(
+                PackageHazardKind::MissingFile,
+                import.as_ref(),
+                require.as_ref().filter(|_| same),
+                None,
+            ));
+        }
+        if require.is_some() && require_s.is_none() && import != require {
+            hazards.push(hazard(
+                PackageHazardKind::MissingFile,
+                None,
+                require.as_ref(),
+                None,
+            ));
+        }
+
+        if let (Some(import), Some(require), Some(import_s), Some(_)) =
+            (&import, &require, &import_s, &require_s)
+        {
+            if import != require && !imports_target(import, import_s, require) {
+                hazards.push(hazard(
+                    PackageHazardKind::DualPackage,
+                    Some(import),
+                    Some(require),
+                    None,
+                ));
+            }
+        }
+
+        if let (Some(target), Some(s)) = (&require, &require_s) {
+            let format = node_format(target, s, package_json, &mut read)
+                .filter(|f| f.format == JsSyntax::ESM || f.syntax == JsSyntax::ESM);
+            if !single_target && format.is_some() {
+                hazards.push(hazard(
+                    PackageHazardKind::RequireEsm,
+                    None,
+                    Some(target),
+                    format,
+                ));
+            }
+        }
+        if let (Some(target), Some(s)) = (&import, &import_s) {
+            let format = node_format(target, s, package_json, &mut read)
+                .filter(|f| f.format == JsSyntax::CJS || f.syntax == JsSyntax::CJS);
+            if !single_target && format.is_some() {
+                hazards.push(hazard(
+                    PackageHazardKind::ImportCjs,
+                    Some(target),
+                    None,
+                    format,
+                ));
+            }
+        }
+    }
+    Some(hazards)
+}
+
+// same as `check_package_hazards`, but reads the package.json and files in the directory
+pub fn check_package_hazards_dir(dir: &Path) -> io::Result<Vec<PackageHazard>> {
+    let package_json = fs::read_to_string(dir.join("package.json"))?;
+    check_package_hazards(&package_json, |target| {
+        let b = fs::read(dir.join(target)).ok()?;
+        Some(String::from_utf8_lossy(&b).into_owned())
+    })
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid package.json"))
+}
+
+// the subpaths of `exports` without patterns, `.` for the sugar forms
+fn export_subpaths(json: &JsonValue) -> Vec<String> {
+    match json.get("exports") {
+        None | Some(JsonValue::Null) => Vec::new(),
+        Some(JsonValue::Object(entries)) if entries.iter().any(|(k, _)| k.starts_with('.')) => {
+            let mut subpaths: Vec<String> = Vec::new();
+            for (key, _) in entries {
+                if !key.contains('*') && !subpaths.contains(key) {
+                    subpaths.push(key.clone());
+                }
+            }
+            subpaths
+        }
+        Some(_) => vec![".".to_string()],
+    }
+}
+
+// whether the esm file imports or re-exports the target, the wrapper that avoids the hazard
+fn imports_target(path: &str, s: &str, target: &str) -> bool {
+    let dialect = path
+        .rsplit_once('.')
+        .and_then(|(_, ext)| JsDialect::from_extension(ext))
+        .unwrap_or(JsDialect::JS);
+    parse_esm(s, dialect).imports.iter().any(|import| {
+        import
+            .specifier
+            .as_deref()
+            .is_some_and(|specifier| specifier.starts_with('.') && join(path, specifier) == target)
+    })
+}
+
+// how node loads the target, with the nearest package.json in the package
+fn node_format<F>(target: &str, s: &str, package_json: &str, read: &mut F) -> Option<JsNodeFormat>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut dir = target;
+    while let Some((parent, _)) = dir.rsplit_once('/') {
+        dir = parent;
+        if dir == "." {
+            break;
+        }
+        if let Some(nested) = read(&format!("{}/package.json", dir)) {
+            return check_node_format(target, s, Some(&nested));
+        }
+    }
+    check_node_format(target, s, Some(package_json))
+}
+
+// resolve a relative specifier from a file, e.g. `./dist/esm/index.mjs` and `../cjs/index.cjs`
+// give `./dist/cjs/index.cjs`
+fn join(path: &str, specifier: &str) -> String {
+    let mut segments: Vec<&str> = path.split('/').collect();
+    segments.pop();
+    for segment in specifier.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.len() > 1 => {
+                segments.pop();
+            }
+            ".." => {}
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
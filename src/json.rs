@@ -0,0 +1,230 @@
+// a json value, as needed to read package.json files. objects keep the order of their keys, as
+// the order of conditions in `exports` matters
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    // the value of the key if this is an object. the last one wins for duplicate keys
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => {
+                entries.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+// parse json, returns `None` if it's invalid
+pub fn parse_json(s: &str) -> Option<JsonValue> {
+    // node ignores the byte order mark of package.json files
+    let s = s.strip_prefix('\u{FEFF}').unwrap_or(s);
+    let mut parser = JsonParser {
+        b: s.as_bytes(),
+        i: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.i != parser.b.len() {
+        return None;
+    }
+    Some(value)
+}
+
+// nesting limit, so deeply nested input can't overflow the stack
+const MAX_DEPTH: usize = 512;
+
+struct JsonParser<'a> {
+    b: &'a [u8],
+    i: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .b
+            .get(self.i)
+            .is_some_and(|v| matches!(v, b' ' | b'\t' | b'\n' | b'\r'))
+        {
+            self.i += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.b.get(self.i) == Some(&c) {
+            self.i += 1;
+            return true;
+        }
+        false
+    }
+
+    fn value(&mut self) -> Option<JsonValue> {
+        self.skip_whitespace();
+        let rest = &self.b[self.i..];
+        let value = match *rest.first()? {
+            b'{' => self.nested(|p| p.object())?,
+            b'[' => self.nested(|p| p.array())?,
+            b'"' => JsonValue::String(self.string()?),
+            _ if rest.starts_with(b"null") => {
+                self.i += 4;
+                JsonValue::Null
+            }
+            _ if rest.starts_with(b"true") => {
+                self.i += 4;
+                JsonValue::Bool(true)
+            }
+            _ if rest.starts_with(b"false") => {
+                self.i += 5;
+                JsonValue::Bool(false)
+            }
+            _ => JsonValue::Number(self.number()?),
+        };
+        Some(value)
+    }
+
+    fn nested<F>(&mut self, f: F) -> Option<JsonValue>
+    where
+        F: FnOnce(&mut Self) -> Option<JsonValue>,
+    {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return None;
+        }
+        let value = f(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Option<JsonValue> {
+        self.i += 1;
+        let mut entries = Vec::new();
+        if self.eat(b'}') {
+            return Some(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.b.get(self.i) != Some(&b'"') {
+                return None;
+            }
+            let key = self.string()?;
+            if !self.eat(b':') {
+                return None;
+            }
+            entries.push((key, self.value()?));
+            if self.eat(b'}') {
+                return Some(JsonValue::Object(entries));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<JsonValue> {
+        self.i += 1;
+        let mut items = Vec::new();
+        if self.eat(b']') {
+            return Some(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat(b']') {
+                return Some(JsonValue::Array(items));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let mut s = String::new();
+        self.i += 1;
+        loop {
+            // copy everything until the next quote or escape at once
+            let end = self.i
+                + self.b[self.i..]
+                    .iter()
+                    .position(|&v| v == b'"' || v == b'\\' || v < 0x20)?;
+            s.push_str(std::str::from_utf8(&self.b[self.i..end]).ok()?);
+            self.i = end + 1;
+            match self.b[end] {
+                b'"' => return Some(s),
+                b'\\' => {}
+                // control characters must be escaped
+                _ => return None,
+            }
+            let c = *self.b.get(self.i)?;
+            self.i += 1;
+            match c {
+                b'"' => s.push('"'),
+                b'\\' => s.push('\\'),
+                b'/' => s.push('/'),
+                b'b' => s.push('\u{8}'),
+                b'f' => s.push('\u{c}'),
+                b'n' => s.push('\n'),
+                b'r' => s.push('\r'),
+                b't' => s.push('\t'),
+                b'u' => {
+                    let high = self.hex4()?;
+                    let code = if (0xd800..0xdc00).contains(&high)
+                        && self.b[self.i..].starts_with(b"\\u")
+                    {
+                        self.i += 2;
+                        let low = self.hex4()?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return None;
+                        }
+                        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        high
+                    };
+                    // lone surrogates can't be in a rust string
+                    s.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let hex = self.b.get(self.i..self.i + 4)?;
+        if !hex.iter().all(u8::is_ascii_hexdigit) {
+            return None;
+        }
+        self.i += 4;
+        u32::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let start = self.i;
+        while self
+            .b
+            .get(self.i)
+            .is_some_and(|v| v.is_ascii_digit() || matches!(v, b'-' | b'+' | b'.' | b'e' | b'E'))
+        {
+            self.i += 1;
+        }
+        std::str::from_utf8(&self.b[start..self.i])
+            .ok()?
+            .parse()
+            .ok()
+    }
+}
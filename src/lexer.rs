@@ -0,0 +1,329 @@
+use crate::diagnostic::JsDiagnosticKind;
+use crate::position::{LineIndex, Span};
+use crate::utils::{is_backslash_escaped, unescape_js_string};
+use crate::walk::{WalkItemKind, Walker};
+use crate::JsDialect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Identifier,
+    Punctuator,
+    String,
+    // a template literal chunk, e.g. `foo${, }bar${ or }baz`
+    Template,
+    Regex,
+    Comment,
+    Number,
+    // jsx markup, e.g. `<div className="foo">`, `text` or the braces of `{expr}`
+    Jsx,
+}
+
+// a lexed token. `start` and `end` are byte offsets into the source, `end` is exclusive. use
+// `span` for lines, columns and utf-16 offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, s: &'a str) -> &'a str {
+        &s[self.start..self.end]
+    }
+
+    // the line, column and utf-16 offsets of the token, with the index of the source it's from
+    pub fn span(&self, index: &LineIndex) -> Span {
+        index.span(self.start, self.end)
+    }
+}
+
+// multi-byte punctuators, sorted by length so the longest is matched first. anything else is
+// a single byte punctuator
+const PUNCTUATORS: [&[u8]; 33] = [
+    b">>>=", b"...", b"===", b"!==", b"**=", b"<<=", b">>=", b">>>", b"&&=", b"||=", b"??=", b"=>",
+    b"==", b"!=", b"<=", b">=", b"&&", b"||", b"??", b"?.", b"++", b"--", b"+=", b"-=", b"*=",
+    b"/=", b"%=", b"&=", b"|=", b"^=", b"<<", b">>", b"**",
+];
+
+// token iterator built on top of the walker, so it shares the same heuristics for strings,
+// comments, template literals and regexes as the rest of the crate.
+pub struct Lexer<'a> {
+    s: &'a str,
+    b: &'a [u8],
+    walker: Walker<'a>,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self::with_dialect(s, JsDialect::JS)
+    }
+
+    pub fn with_dialect(s: &'a str, dialect: JsDialect) -> Self {
+        Lexer {
+            s,
+            b: s.as_bytes(),
+            walker: Walker::with_dialect(s, dialect),
+        }
+    }
+
+    // the remaining tokens with their spans, mapped through a single line index of the source
+    pub fn spanned(self) -> impl Iterator<Item = (Token, Span)> + 'a {
+        let index = LineIndex::new(self.s);
+        self.map(move |token| (token, token.span(&index)))
+    }
+
+    // unterminated strings, comments, template literals and regexes lexed so far, and the
+    // ambiguous slashes and unbalanced braces the heuristics had to guess at, with their byte
+    // ranges
+    pub fn diagnostics(&self) -> &[(JsDiagnosticKind, usize, usize)] {
+        &self.walker.diagnostics
+    }
+
+    fn token(&mut self, kind: TokenKind, start: usize, end: usize) -> Option<Token> {
+        self.walker.i = end;
+        Some(Token { kind, start, end })
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let b = self.b;
+
+        loop {
+            let item = self.walker.next()?;
+            let start = item.start;
+
+            let kind = match item.kind {
+                WalkItemKind::Code => None,
+                WalkItemKind::Bom => continue,
+                WalkItemKind::Hashbang
+                | WalkItemKind::LineComment
+                | WalkItemKind::BlockComment
+                | WalkItemKind::HtmlComment => Some(TokenKind::Comment),
+                WalkItemKind::String => Some(TokenKind::String),
+                WalkItemKind::Template => Some(TokenKind::Template),
+                WalkItemKind::Regex => Some(TokenKind::Regex),
+                WalkItemKind::Jsx => Some(TokenKind::Jsx),
+            };
+            if let Some(kind) = kind {
+                return Some(Token {
+                    kind,
+                    start,
+                    end: item.end,
+                });
+            }
+
+            let c = b[start];
+
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+
+            if is_identifier_start(c) {
+                let end = scan_while(b, start + 1, is_identifier_part);
+                return self.token(TokenKind::Identifier, start, end);
+            }
+
+            if c.is_ascii_digit() || (c == b'.' && b.get(start + 1).is_some_and(u8::is_ascii_digit))
+            {
+                return self.token(TokenKind::Number, start, scan_number(b, start));
+            }
+
+            // `a?.5:0` is a ternary, not optional chaining
+            let len = PUNCTUATORS
+                .iter()
+                .find(|p| {
+                    b[start..].starts_with(p)
+                        && !(*p == b"?." && b.get(start + 2).is_some_and(u8::is_ascii_digit))
+                })
+                .map_or(1, |p| p.len());
+            return self.token(TokenKind::Punctuator, start, start + len);
+        }
+    }
+}
+
+fn is_identifier_start(c: u8) -> bool {
+    // non-ascii bytes are treated as part of identifiers so spans stay on char boundaries
+    c.is_ascii_alphabetic() || c == b'_' || c == b'$' || c == b'#' || c == b'\\' || c >= 0x80
+}
+
+fn is_identifier_part(c: u8) -> bool {
+    is_identifier_start(c) && c != b'#' || c.is_ascii_digit()
+}
+
+fn scan_while(b: &[u8], from: usize, f: fn(u8) -> bool) -> usize {
+    b[from..]
+        .iter()
+        .position(|&v| !f(v))
+        .map_or(b.len(), |pos| from + pos)
+}
+
+// numbers are loosely scanned, including separators, bigint suffixes and exponent signs
+fn scan_number(b: &[u8], start: usize) -> usize {
+    let is_hex = b[start] == b'0' && matches!(b.get(start + 1), Some(b'x' | b'X'));
+    let mut j = start;
+    while j < b.len() {
+        let v = b[j];
+        let is_exponent_sign =
+            (v == b'+' || v == b'-') && !is_hex && matches!(b[j - 1], b'e' | b'E');
+        if !(v.is_ascii_alphanumeric() || v == b'_' || v == b'.' || is_exponent_sign) {
+            break;
+        }
+        j += 1;
+    }
+    j
+}
+
+// tokens that can only start a new statement, used to know when a declaration ends
+pub const STATEMENT_KEYWORDS: [&str; 13] = [
+    "import", "export", "const", "let", "var", "function", "class", "if", "for", "while", "do",
+    "return", "throw",
+];
+
+// collect the binding names of every declarator of `var`, `let` or `const`, starting after the
+// keyword, e.g. `foo = 1, { bar } = baz`. returns the index after the declaration
+pub fn collect_declaration_names(
+    s: &str,
+    tokens: &[Token],
+    i: usize,
+    names: &mut Vec<usize>,
+) -> usize {
+    let mut j = i;
+    loop {
+        j = collect_binding_names(s, tokens, j, names);
+        // skip the type annotation and initializer until the next declarator
+        let mut depth: usize = 0;
+        while let Some(token) = tokens.get(j) {
+            let text = token.text(s);
+            if token.kind == TokenKind::Punctuator {
+                match text {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" => {
+                        if depth == 0 {
+                            return j;
+                        }
+                        depth -= 1;
+                    }
+                    ";" if depth == 0 => return j + 1,
+                    "," if depth == 0 => break,
+                    _ => {}
+                }
+            } else if token.kind == TokenKind::Identifier
+                && depth == 0
+                && STATEMENT_KEYWORDS.contains(&text)
+            {
+                return j;
+            }
+            j += 1;
+        }
+        if j >= tokens.len() {
+            return j;
+        }
+        // skip ,
+        j += 1;
+    }
+}
+
+// collect the binding identifiers of a declaration, e.g. `foo`, `{ foo, bar: [baz] }`. pushes the
+// token indices of the names and returns the index after the binding pattern.
+pub fn collect_binding_names(s: &str, tokens: &[Token], i: usize, names: &mut Vec<usize>) -> usize {
+    let token = match tokens.get(i) {
+        Some(token) => token,
+        None => return i,
+    };
+    match (token.kind, token.text(s)) {
+        (TokenKind::Identifier, _) => {
+            names.push(i);
+            i + 1
+        }
+        (TokenKind::Punctuator, open @ ("{" | "[")) => {
+            let close = if open == "{" { "}" } else { "]" };
+            let mut j = i + 1;
+            while let Some(token) = tokens.get(j) {
+                let text = token.text(s);
+                if token.kind == TokenKind::Punctuator {
+                    match text {
+                        t if t == close => return j + 1,
+                        "," | "..." => {
+                            j += 1;
+                            continue;
+                        }
+                        "=" => {
+                            // skip default value until the next , or the closing bracket
+                            j = skip_expression(s, tokens, j + 1);
+                            continue;
+                        }
+                        // { [key]: value }
+                        "[" if open == "{" => {
+                            j = skip_expression(s, tokens, j + 1) + 1;
+                            let is_key = tokens.get(j).is_some_and(|t| t.text(s) == ":");
+                            if is_key {
+                                j = collect_binding_names(s, tokens, j + 1, names);
+                            }
+                            continue;
+                        }
+                        "{" | "[" => {
+                            j = collect_binding_names(s, tokens, j, names);
+                            continue;
+                        }
+                        _ => return j,
+                    }
+                }
+                // { key: value } or { [key]: value }, the value is the binding
+                let is_key = tokens
+                    .get(j + 1)
+                    .is_some_and(|t| t.kind == TokenKind::Punctuator && t.text(s) == ":");
+                let next = if open == "{" && is_key {
+                    collect_binding_names(s, tokens, j + 2, names)
+                } else {
+                    collect_binding_names(s, tokens, j, names)
+                };
+                // skip tokens that can't be bindings, e.g. `[1]`, so the loop always advances
+                j = if next == j { j + 1 } else { next };
+            }
+            j
+        }
+        _ => i,
+    }
+}
+
+// skip an expression until a , or closing bracket at the same depth
+pub fn skip_expression(s: &str, tokens: &[Token], i: usize) -> usize {
+    let mut depth: usize = 0;
+    let mut j = i;
+    while let Some(token) = tokens.get(j) {
+        if token.kind == TokenKind::Punctuator {
+            match token.text(s) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    if depth == 0 {
+                        return j;
+                    }
+                    depth -= 1;
+                }
+                "," if depth == 0 => return j,
+                _ => {}
+            }
+        }
+        j += 1;
+    }
+    j
+}
+
+// the decoded value of a string or a template literal without expressions, along with the byte
+// range of its content without quotes
+pub fn static_string_value(s: &str, token: &Token) -> Option<(String, usize, usize)> {
+    let raw = token.text(s);
+    // unterminated strings are still lexed as strings
+    let b = raw.as_bytes();
+    let is_closed = b.len() >= 2 && b[0] == b[b.len() - 1] && !is_backslash_escaped(b, b.len() - 1);
+    let is_plain_template = token.kind == TokenKind::Template && raw.starts_with('`');
+    if !is_closed || (token.kind != TokenKind::String && !is_plain_template) {
+        return None;
+    }
+    let inner = &raw[1..raw.len() - 1];
+    Some((unescape_js_string(inner), token.start + 1, token.end - 1))
+}
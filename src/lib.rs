@@ -1,19 +1,85 @@
 // suppress wasm-bindgen auto-generated name warning
 #![allow(non_snake_case, non_upper_case_globals)]
 
+mod bytes;
+mod cjs;
+mod comment;
+mod diagnostic;
+mod directive;
+mod esm;
+mod exports;
+mod format;
+#[cfg(feature = "scan")]
+mod glob;
+mod hazard;
+mod json;
+mod lexer;
+mod node;
+mod position;
+mod rewrite;
+#[cfg(feature = "scan")]
+mod scan;
+mod scope;
+mod source_map;
+mod stream;
+mod syntax;
 mod utils;
 mod walk;
 
-use utils::{
-    is_export_identifier, is_exports_identifier, is_function_param_declaration,
-    is_import_identifier, is_meta_identifier, is_module_identifier, is_require_identifier,
-    is_var_declaration,
+pub use bytes::{
+    analyze_js_syntax_bytes, check_node_format_bytes, directives_bytes, guess_js_format_bytes,
+    guess_js_syntax_bytes, guess_js_syntax_with_confidence_bytes, hashbang_bytes, parse_cjs_bytes,
+    parse_comments_bytes, parse_esm_bytes, parse_requires_bytes, parse_source_map_comments_bytes,
+    resolve_package_bytes,
 };
-use walk::{walk, WalkCallbackResult};
+pub use cjs::{CjsParseResult, CjsRequire};
+pub use comment::{JsComment, JsCommentAnnotation, JsCommentAnnotationKind, JsCommentKind};
+pub use diagnostic::{JsDiagnostic, JsDiagnosticKind};
+pub use directive::{parse_directives, parse_hashbang, JsDirective};
+pub use esm::{EsmExport, EsmExportKind, EsmImport, EsmImportKind, EsmParseResult};
+pub use exports::{
+    resolve_package_exports, resolve_package_imports, resolve_package_specifier,
+    JsPackageResolution, JsResolveError,
+};
+pub use format::JsFormat;
+// only for the fmu binary
+#[cfg(feature = "scan")]
+#[doc(hidden)]
+pub use glob::glob_match;
+pub use hazard::{
+    check_package_hazards, check_package_hazards_dir, PackageHazard, PackageHazardKind,
+};
+pub use json::{parse_json, JsonValue};
+pub use lexer::{Lexer, Token, TokenKind};
+pub use node::{
+    check_node_format_file, find_package_json, resolve_node_format, JsNodeFormat,
+    JsNodeFormatReason,
+};
+pub use position::{LineIndex, Span};
+pub use rewrite::rewrite_specifiers;
+#[cfg(feature = "scan")]
+pub use scan::{scan_dir, FileScan, PackageScan, ScanOptions, ScanResult};
+pub use source_map::{JsSourceMapComment, JsSourceMapCommentKind, JsSourceMapComments};
+pub use stream::JsSyntaxStream;
+pub use syntax::{
+    JsConfidence, JsSyntaxAnalysis, JsSyntaxEvidence, JsSyntaxEvidenceKind, JsSyntaxGuess,
+};
+
+use syntax::scan_js_syntax;
+use walk::WalkCallbackResult;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-#[derive(Debug, PartialEq, Eq)]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "(specifier: string) => string | undefined")]
+    pub type SpecifierRewriter;
+
+    #[wasm_bindgen(method, js_name = "call")]
+    fn call(this: &SpecifierRewriter, this_arg: &JsValue, specifier: &str) -> JsValue;
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JsSyntax {
     ESM,
     CJS,
@@ -21,132 +87,226 @@ pub enum JsSyntax {
     Unknown,
 }
 
+// the language flavor of the source, as some syntax can't be detected without knowing it
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsDialect {
+    JS,
+    TS,
+    JSX,
+    TSX,
+}
+
+impl JsDialect {
+    pub fn is_ts(&self) -> bool {
+        matches!(self, JsDialect::TS | JsDialect::TSX)
+    }
+
+    pub fn is_jsx(&self) -> bool {
+        matches!(self, JsDialect::JSX | JsDialect::TSX)
+    }
+
+    // guess the dialect from a file extension without the leading dot, e.g. `mts`
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "js" | "mjs" | "cjs" => Some(JsDialect::JS),
+            "ts" | "mts" | "cts" => Some(JsDialect::TS),
+            "jsx" => Some(JsDialect::JSX),
+            "tsx" => Some(JsDialect::TSX),
+            _ => None,
+        }
+    }
+}
+
+impl JsSyntax {
+    fn from_flags(is_esm: bool, is_cjs: bool) -> Self {
+        if is_esm && is_cjs {
+            JsSyntax::Mixed
+        } else if is_esm {
+            JsSyntax::ESM
+        } else if is_cjs {
+            JsSyntax::CJS
+        } else {
+            JsSyntax::Unknown
+        }
+    }
+}
+
 // detect file syntax esm or cjs
 #[wasm_bindgen(js_name = "guessJsSyntax")]
 pub fn guess_js_syntax(s: &str) -> JsSyntax {
+    guess_js_syntax_with_dialect(s, JsDialect::JS)
+}
+
+// same as `guess_js_syntax`, but handles syntax specific to the dialect, e.g. typescript's
+// `import foo = require('foo')` and `export =`
+#[wasm_bindgen(js_name = "guessJsSyntaxWithDialect")]
+pub fn guess_js_syntax_with_dialect(s: &str, dialect: JsDialect) -> JsSyntax {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 
     let mut is_esm = false;
     let mut is_cjs = false;
 
-    // shadowing
-    // default depth is 0, every open brace increments, closing brace decrements.
-    // this happens for JS objects too but for us, it's good enough
-    let mut scope_depth: usize = 0;
-    let mut require_shadowed_depth = usize::MAX;
-    let mut module_shadowed_depth = usize::MAX;
-    let mut exports_shadowed_depth = usize::MAX;
-
-    walk(s, |b, i, c| {
-        print!("{}", c as char);
+    scan_js_syntax(s, dialect, |kind, _, _| {
+        match kind.syntax() {
+            JsSyntax::ESM => is_esm = true,
+            JsSyntax::CJS => is_cjs = true,
+            _ => {}
+        }
         if is_esm && is_cjs {
-            return WalkCallbackResult::Break;
+            WalkCallbackResult::Break
+        } else {
+            WalkCallbackResult::Continue
         }
+    });
 
-        // esm specific detection
-        if !is_esm {
-            // top-level import
-            if is_import_identifier(&b, *i) {
-                // TODO: handle space between import.meta, but why would someone do that
-                if b[*i + 1] == b'.' && is_meta_identifier(&b, *i + 2) {
-                    is_esm = true;
-                    *i += 11;
-                } else {
-                    // TODO: handle \r\n?
-                    for &v in b[*i + 6..].iter() {
-                        if v == b'\'' || v == b'"' || v == b'{' || v == b'\n' {
-                            is_esm = true;
-                            break;
-                        } else if v == b'(' {
-                            // dynamic import
-                            break;
-                        }
-                    }
-                    *i += 6;
-                }
-                return WalkCallbackResult::Continue;
-            }
-
-            // top-level export
-            if is_export_identifier(&b, *i) {
-                is_esm = true;
-                *i += 6;
-                return WalkCallbackResult::Continue;
-            }
-        }
+    JsSyntax::from_flags(is_esm, is_cjs)
+}
 
-        if !is_cjs {
-            // track scope depth
-            // NOTE: track in cjs only as it's only relevant for it
-            // TODO: track `=>` and `?:` scoped (pita)
-            if c == b'{' {
-                scope_depth += 1;
-            } else if c == b'}' {
-                scope_depth = scope_depth.saturating_sub(1);
-                // re-concile shadowed depth, if we exit the scope that has been
-                // shadowed by require, module, or exports, reset them
-                if scope_depth < require_shadowed_depth {
-                    require_shadowed_depth = usize::MAX;
-                }
-                if scope_depth < module_shadowed_depth {
-                    module_shadowed_depth = usize::MAX;
-                }
-                if scope_depth < exports_shadowed_depth {
-                    exports_shadowed_depth = usize::MAX;
-                }
-            }
-
-            // require reference
-            if scope_depth < require_shadowed_depth && is_require_identifier(&b, *i) {
-                if is_var_declaration(&b, *i) {
-                    require_shadowed_depth = scope_depth;
-                } else if is_function_param_declaration(&b, *i, *i + 7) {
-                    require_shadowed_depth = scope_depth + 1;
-                } else {
-                    is_cjs = true;
-                }
-                *i += 7;
-                return WalkCallbackResult::Continue;
-            }
-
-            // module reference
-            if scope_depth < module_shadowed_depth && is_module_identifier(&b, *i) {
-                if is_var_declaration(&b, *i) {
-                    module_shadowed_depth = scope_depth;
-                } else if is_function_param_declaration(&b, *i, *i + 6) {
-                    module_shadowed_depth = scope_depth + 1;
-                } else {
-                    is_cjs = true;
-                }
-                *i += 6;
-                return WalkCallbackResult::Continue;
-            }
-
-            // exports reference
-            if scope_depth < exports_shadowed_depth && is_exports_identifier(&b, *i) {
-                if is_var_declaration(&b, *i) {
-                    exports_shadowed_depth = scope_depth;
-                } else if is_function_param_declaration(&b, *i, *i + 7) {
-                    exports_shadowed_depth = scope_depth + 1;
-                } else {
-                    is_cjs = true;
-                }
-                *i += 7;
-                return WalkCallbackResult::Continue;
-            }
-        }
+// same as `guess_js_syntax`, but scans the whole file to also tell how reliable the result is,
+// so a full parser is only needed when the confidence is low
+#[wasm_bindgen(js_name = "guessJsSyntaxWithConfidence")]
+pub fn guess_js_syntax_with_confidence(s: &str, dialect: JsDialect) -> JsSyntaxGuess {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
 
-        return WalkCallbackResult::Continue;
-    });
+    syntax::guess_js_syntax_with_confidence(s, dialect)
+}
 
-    if is_esm && is_cjs {
-        return JsSyntax::Mixed;
-    } else if is_esm {
-        return JsSyntax::ESM;
-    } else if is_cjs {
-        return JsSyntax::CJS;
-    } else {
-        return JsSyntax::Unknown;
-    }
+// detect the module format, e.g. umd, amd or systemjs, which `guess_js_syntax` reports as cjs or
+// unknown
+#[wasm_bindgen(js_name = "guessJsFormat")]
+pub fn guess_js_format(s: &str) -> JsFormat {
+    guess_js_format_with_dialect(s, JsDialect::JS)
+}
+
+#[wasm_bindgen(js_name = "guessJsFormatWithDialect")]
+pub fn guess_js_format_with_dialect(s: &str, dialect: JsDialect) -> JsFormat {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    format::guess_js_format(s, dialect)
+}
+
+// same as `guess_js_syntax`, but also returns every occurrence that led to the result
+#[wasm_bindgen(js_name = "analyzeJsSyntax")]
+pub fn analyze_js_syntax(s: &str) -> JsSyntaxAnalysis {
+    analyze_js_syntax_with_dialect(s, JsDialect::JS)
+}
+
+#[wasm_bindgen(js_name = "analyzeJsSyntaxWithDialect")]
+pub fn analyze_js_syntax_with_dialect(s: &str, dialect: JsDialect) -> JsSyntaxAnalysis {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    syntax::analyze_js_syntax(s, dialect)
+}
+
+// find all imports and exports of an es module
+#[wasm_bindgen(js_name = "parseEsm")]
+pub fn parse_esm(s: &str, dialect: JsDialect) -> EsmParseResult {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    esm::parse_esm(s, dialect)
+}
+
+// find the export names and re-exports of a commonjs module
+#[wasm_bindgen(js_name = "parseCjs")]
+pub fn parse_cjs(s: &str, dialect: JsDialect) -> CjsParseResult {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    cjs::parse_cjs(s, dialect)
+}
+
+// find all `require()` calls that aren't shadowed
+#[wasm_bindgen(js_name = "parseRequires")]
+pub fn parse_requires(s: &str, dialect: JsDialect) -> Vec<CjsRequire> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    cjs::parse_requires(s, dialect)
+}
+
+// find all comments, with annotations like `/*#__PURE__*/` or `/* webpackChunkName: "foo" */`
+#[wasm_bindgen(js_name = "parseComments")]
+pub fn parse_comments(s: &str, dialect: JsDialect) -> Vec<JsComment> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    comment::parse_comments(s, dialect)
+}
+
+// find the `//# sourceMappingURL=` and `//# sourceURL=` comments at the end of the file, and
+// optionally remove them
+#[wasm_bindgen(js_name = "parseSourceMapComments")]
+pub fn parse_source_map_comments(s: &str, dialect: JsDialect, strip: bool) -> JsSourceMapComments {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    source_map::parse_source_map_comments(s, dialect, strip)
+}
+
+// compare how node loads the file, from its extension and the `"type"` of the nearest
+// package.json, with what its code looks like. returns `undefined` for files node doesn't load,
+// e.g. `.jsx`
+#[wasm_bindgen(js_name = "checkNodeFormat")]
+pub fn check_node_format(
+    path: &str,
+    s: &str,
+    package_json: Option<String>,
+) -> Option<JsNodeFormat> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    node::check_node_format(path, s, package_json.as_deref())
+}
+
+// resolve `pkg/sub/path` or `#internal` with the `exports` or `imports` of the package.json, for
+// conditions like `["node", "import"]`
+#[wasm_bindgen(js_name = "resolvePackage")]
+pub fn resolve_package(
+    package_json: &str,
+    specifier: &str,
+    conditions: Vec<String>,
+) -> JsPackageResolution {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    let Some(package_json) = parse_json(package_json) else {
+        return Err(JsResolveError::InvalidPackageConfig).into();
+    };
+    let conditions: Vec<&str> = conditions.iter().map(String::as_str).collect();
+    resolve_package_specifier(&package_json, specifier, &conditions).into()
+}
+
+// read the directives at the start of the file, e.g. `'use strict'` or `'use client'`
+#[wasm_bindgen(js_name = "directives")]
+pub fn directives(s: &str) -> Vec<JsDirective> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    parse_directives(s)
+}
+
+// read the `#!` line at the start of the file, without `#!`
+#[wasm_bindgen(js_name = "hashbang")]
+pub fn hashbang(s: &str) -> Option<String> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    parse_hashbang(s)
+}
+
+// replace every module specifier with the value returned by the js function
+#[wasm_bindgen(js_name = "rewriteSpecifiers")]
+pub fn rewrite_specifiers_js(s: &str, dialect: JsDialect, rewriter: &SpecifierRewriter) -> String {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    rewrite_specifiers(s, dialect, |specifier| {
+        rewriter.call(&JsValue::UNDEFINED, specifier).as_string()
+    })
 }
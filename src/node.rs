@@ -0,0 +1,106 @@
+use crate::json::parse_json;
+use crate::{guess_js_syntax_with_dialect, JsDialect, JsSyntax};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use wasm_bindgen::prelude::*;
+
+// why node loads a file as esm or cjs
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsNodeFormatReason {
+    // `.mjs`, `.cjs`, `.mts` or `.cts`
+    Extension,
+    // `"type": "module"` or `"type": "commonjs"` in the nearest package.json
+    PackageType,
+    // no package.json or `"type"`, which defaults to cjs. like node's syntax detection, code
+    // with esm syntax is loaded as esm
+    Default,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsNodeFormat {
+    // `ESM` or `CJS`, how node loads the file
+    pub format: JsSyntax,
+    pub reason: JsNodeFormatReason,
+    // what the code looks like, from `guess_js_syntax`
+    pub syntax: JsSyntax,
+    // whether the syntax doesn't work in the format, e.g. `import` in a cjs file or `require` in
+    // an esm file
+    pub mismatch: bool,
+}
+
+// how node loads the file at `path`, given the contents of the nearest package.json, before
+// syntax detection. returns `None` for files node doesn't load, e.g. `.jsx`, `.tsx` or `.json`
+pub fn resolve_node_format(
+    path: &str,
+    package_json: Option<&str>,
+) -> Option<(JsSyntax, JsNodeFormatReason)> {
+    let extension = Path::new(path).extension()?.to_str()?;
+    match extension {
+        "js" | "ts" => {}
+        "mjs" | "mts" => return Some((JsSyntax::ESM, JsNodeFormatReason::Extension)),
+        "cjs" | "cts" => return Some((JsSyntax::CJS, JsNodeFormatReason::Extension)),
+        _ => return None,
+    }
+
+    let package_type = package_json
+        .and_then(parse_json)
+        .and_then(|json| json.get("type")?.as_str().map(str::to_string));
+    match package_type.as_deref() {
+        Some("module") => Some((JsSyntax::ESM, JsNodeFormatReason::PackageType)),
+        Some("commonjs") => Some((JsSyntax::CJS, JsNodeFormatReason::PackageType)),
+        _ => Some((JsSyntax::CJS, JsNodeFormatReason::Default)),
+    }
+}
+
+// compare how node loads the file with what its code looks like
+pub fn check_node_format(path: &str, s: &str, package_json: Option<&str>) -> Option<JsNodeFormat> {
+    let (mut format, reason) = resolve_node_format(path, package_json)?;
+    let extension = Path::new(path).extension()?.to_str()?;
+    let dialect = JsDialect::from_extension(extension)?;
+    let syntax = guess_js_syntax_with_dialect(s, dialect);
+    // node tries esm when the file fails to load as cjs because of esm syntax
+    if reason == JsNodeFormatReason::Default && matches!(syntax, JsSyntax::ESM | JsSyntax::Mixed) {
+        format = JsSyntax::ESM;
+    }
+
+    let mismatch = syntax != JsSyntax::Unknown && syntax != format;
+
+    Some(JsNodeFormat {
+        format,
+        reason,
+        syntax,
+        mismatch,
+    })
+}
+
+// the package.json that decides the format of the file, like node it's the nearest one, but
+// not above the `node_modules` directory the file is in
+pub fn find_package_json(path: &Path) -> Option<PathBuf> {
+    for dir in path.ancestors().skip(1) {
+        if dir.file_name().is_some_and(|name| name == "node_modules") {
+            return None;
+        }
+        let package_json = dir.join("package.json");
+        if package_json.is_file() {
+            return Some(package_json);
+        }
+    }
+    None
+}
+
+// same as `check_node_format`, but reads the file and its nearest package.json
+pub fn check_node_format_file(path: &Path) -> io::Result<Option<JsNodeFormat>> {
+    let s = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let package_json = match find_package_json(path) {
+        Some(package_json) => Some(fs::read_to_string(package_json)?),
+        None => None,
+    };
+    Ok(check_node_format(
+        &path.to_string_lossy(),
+        &s,
+        package_json.as_deref(),
+    ))
+}
@@ -0,0 +1,112 @@
+use wasm_bindgen::prelude::*;
+
+// a range in the source. `start` and `end` are utf-8 byte offsets for indexing rust strings,
+// `utf16_start` and `utf16_end` are utf-16 code unit offsets for indexing js strings. `line` is
+// 1-based and `column` is 0-based in utf-16 code units, both for `start`. the ends are exclusive.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub utf16_start: usize,
+    pub utf16_end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+// how many bytes between each utf-16 checkpoint, so converting an offset only counts a few chars
+// even for minified code on a single line
+const CHECKPOINT_INTERVAL: usize = 256;
+
+// maps byte offsets to lines, columns and utf-16 offsets
+pub struct LineIndex<'a> {
+    s: &'a str,
+    // byte offsets of each line start
+    line_starts: Vec<usize>,
+    // (byte offset, utf-16 offset) pairs on char boundaries. empty if the source is ascii-only,
+    // where both offsets are the same
+    checkpoints: Vec<(usize, usize)>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(s: &'a str) -> Self {
+        let b = s.as_bytes();
+        let mut line_starts = vec![0];
+        let mut i = 0;
+        while i < b.len() {
+            match b[i] {
+                b'\n' => line_starts.push(i + 1),
+                b'\r' if b.get(i + 1) != Some(&b'\n') => line_starts.push(i + 1),
+                // U+2028 and U+2029 are line terminators too
+                0xE2 if b.get(i + 1) == Some(&0x80)
+                    && matches!(b.get(i + 2), Some(0xA8 | 0xA9)) =>
+                {
+                    line_starts.push(i + 3);
+                    i += 2;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let mut checkpoints = Vec::new();
+        if !s.is_ascii() {
+            let mut utf16_offset = 0;
+            let mut next_checkpoint = 0;
+            for (offset, c) in s.char_indices() {
+                if offset >= next_checkpoint {
+                    checkpoints.push((offset, utf16_offset));
+                    next_checkpoint = offset + CHECKPOINT_INTERVAL;
+                }
+                utf16_offset += c.len_utf16();
+            }
+        }
+
+        LineIndex {
+            s,
+            line_starts,
+            checkpoints,
+        }
+    }
+
+    pub fn utf16_offset(&self, offset: usize) -> usize {
+        let offset = offset.min(self.s.len());
+        if self.checkpoints.is_empty() {
+            return offset;
+        }
+        let index = match self.checkpoints.binary_search_by_key(&offset, |v| v.0) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let (checkpoint, utf16_offset) = self.checkpoints[index];
+        // the offset may not be on a char boundary, count up to the char it's in
+        let counted: usize = self.s[checkpoint..]
+            .char_indices()
+            .take_while(|(i, _)| checkpoint + i < offset)
+            .map(|(_, c)| c.len_utf16())
+            .sum();
+        utf16_offset + counted
+    }
+
+    // 1-based line and 0-based column in utf-16 code units
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let column = self.utf16_offset(offset) - self.utf16_offset(self.line_starts[line]);
+        (line + 1, column)
+    }
+
+    pub fn span(&self, start: usize, end: usize) -> Span {
+        let (line, column) = self.line_column(start);
+        Span {
+            start,
+            end,
+            utf16_start: self.utf16_offset(start),
+            utf16_end: self.utf16_offset(end),
+            line,
+            column,
+        }
+    }
+}
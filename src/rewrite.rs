@@ -0,0 +1,60 @@
+use crate::cjs::parse_requires;
+use crate::esm::parse_esm;
+use crate::JsDialect;
+
+// replace every module specifier in imports, exports and requires with the value returned by the
+// callback. return `None` to keep the specifier as is. all other bytes are preserved.
+pub fn rewrite_specifiers<F>(s: &str, dialect: JsDialect, mut f: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let esm = parse_esm(s, dialect);
+    let requires = parse_requires(s, dialect);
+
+    let mut edits: Vec<(usize, usize, &str)> = Vec::new();
+    let specifiers = esm
+        .imports
+        .iter()
+        .map(|v| (v.specifier_span, &v.specifier))
+        .chain(requires.iter().map(|v| (v.specifier_span, &v.specifier)));
+    for (span, specifier) in specifiers {
+        if let (Some(span), Some(specifier)) = (span, specifier) {
+            edits.push((span.start, span.end, specifier));
+        }
+    }
+    edits.sort_by_key(|v| v.0);
+    edits.dedup_by_key(|v| v.0);
+
+    let mut result = String::with_capacity(s.len());
+    let mut last = 0;
+    for (start, end, specifier) in edits {
+        let replacement = match f(specifier) {
+            Some(replacement) => replacement,
+            None => continue,
+        };
+        // the quote is right before the specifier
+        let quote = s.as_bytes()[start - 1];
+        result.push_str(&s[last..start]);
+        push_escaped(&mut result, &replacement, quote);
+        last = end;
+    }
+    result.push_str(&s[last..]);
+    result
+}
+
+// escape the specifier so it stays valid within its quotes
+fn push_escaped(result: &mut String, specifier: &str, quote: u8) {
+    for c in specifier.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '$' if quote == b'`' => result.push_str("\\$"),
+            c if c as u32 == quote as u32 => {
+                result.push('\\');
+                result.push(c);
+            }
+            c => result.push(c),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::glob::glob_match;
+use crate::{guess_js_syntax_bytes, JsDialect, JsSyntax};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    // .gitignore-style patterns relative to the scanned directory, e.g. `test/`, `*.min.js` or
+    // `!keep.js`. the last matching pattern wins
+    pub exclude: Vec<String>,
+    // the number of threads, or 0 to use the available parallelism
+    pub threads: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileScan {
+    pub path: PathBuf,
+    pub syntax: JsSyntax,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageScan {
+    // e.g. `@scope/name` for `node_modules/@scope/name`, or the directory name for the scanned
+    // directory itself
+    pub name: String,
+    pub dir: PathBuf,
+    // sorted by path
+    pub files: Vec<FileScan>,
+    pub esm: usize,
+    pub cjs: usize,
+    pub mixed: usize,
+    pub unknown: usize,
+}
+
+impl PackageScan {
+    // the syntax of the whole package, `Mixed` if it ships both esm and cjs files
+    pub fn syntax(&self) -> JsSyntax {
+        if self.mixed > 0 {
+            return JsSyntax::Mixed;
+        }
+        JsSyntax::from_flags(self.esm > 0, self.cjs > 0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ScanResult {
+    // sorted by directory
+    pub packages: Vec<PackageScan>,
+    // files and directories that couldn't be read
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+// classify every .js, .mjs and .cjs file in the directory in parallel, grouped by the package
+// they belong to. packages are the directories in `node_modules`, symlinks aren't followed so
+// packages linked by pnpm or workspaces are only scanned where they're stored
+pub fn scan_dir(root: &Path, options: &ScanOptions) -> ScanResult {
+    let exclude = Exclude::new(&options.exclude);
+    let mut result = ScanResult::default();
+    let mut files = Vec::new();
+    collect_files(root, root, &exclude, &mut files, &mut result.errors);
+
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let next = AtomicUsize::new(0);
+    let mut scanned: Vec<(usize, io::Result<JsSyntax>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(files.len()).max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut scanned = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(i) else {
+                            return scanned;
+                        };
+                        let syntax = fs::read(path).and_then(|b| {
+                            // a panic is a bug, but it's reported for the file instead of
+                            // losing the files of the thread
+                            panic::catch_unwind(|| guess_js_syntax_bytes(&b, JsDialect::JS))
+                                .map_err(|_| io::Error::other("panicked while scanning"))
+                        });
+                        scanned.push((i, syntax));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    });
+    scanned.sort_by_key(|&(i, _)| i);
+
+    let mut packages: BTreeMap<PathBuf, PackageScan> = BTreeMap::new();
+    for (i, syntax) in scanned {
+        let path = files[i].clone();
+        let syntax = match syntax {
+            Ok(syntax) => syntax,
+            Err(err) => {
+                result.errors.push((path, err));
+                continue;
+            }
+        };
+        let dir = package_dir(root, &path);
+        let package = packages.entry(dir.clone()).or_insert_with(|| PackageScan {
+            name: package_name(root, &dir),
+            dir,
+            files: Vec::new(),
+            esm: 0,
+            cjs: 0,
+            mixed: 0,
+            unknown: 0,
+        });
+        match syntax {
+            JsSyntax::ESM => package.esm += 1,
+            JsSyntax::CJS => package.cjs += 1,
+            JsSyntax::Mixed => package.mixed += 1,
+            JsSyntax::Unknown => package.unknown += 1,
+        }
+        package.files.push(FileScan { path, syntax });
+    }
+
+    result.packages = packages.into_values().collect();
+    result
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    exclude: &Exclude,
+    files: &mut Vec<PathBuf>,
+    errors: &mut Vec<(PathBuf, io::Error)>,
+) {
+    let mut entries =
+        match fs::read_dir(dir).and_then(|entries| entries.collect::<Result<Vec<_>, _>>()) {
+            Ok(entries) => entries,
+            Err(err) => {
+                errors.push((dir.to_path_buf(), err));
+                return;
+            }
+        };
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        if exclude.is_excluded(&relative, file_type.is_dir()) {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_files(root, &path, exclude, files, errors);
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext == "js" || ext == "mjs" || ext == "cjs")
+        {
+            files.push(path);
+        }
+    }
+}
+
+// the innermost `node_modules/name` or `node_modules/@scope/name` directory of the file, or the
+// scanned directory
+fn package_dir(root: &Path, path: &Path) -> PathBuf {
+    let mut dir = root.to_path_buf();
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let components: Vec<_> = relative.components().collect();
+    // the last component is the file
+    let mut i = 0;
+    while i + 1 < components.len() {
+        if components[i].as_os_str() == "node_modules" && i + 2 < components.len() {
+            let name = components[i + 1].as_os_str();
+            let len = if name.to_string_lossy().starts_with('@') && i + 3 < components.len() {
+                3
+            } else {
+                2
+            };
+            dir = root.join(components[..i + len].iter().collect::<PathBuf>());
+            i += len;
+            continue;
+        }
+        i += 1;
+    }
+    dir
+}
+
+fn package_name(root: &Path, dir: &Path) -> String {
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    let components: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    match components.iter().rposition(|c| c == "node_modules") {
+        Some(i) => components[i + 1..].join("/"),
+        None => root
+            .canonicalize()
+            .ok()
+            .as_deref()
+            .unwrap_or(root)
+            .file_name()
+            .map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+    }
+}
+
+// .gitignore-style patterns
+struct Exclude {
+    // (pattern, negated, directories only)
+    patterns: Vec<(String, bool, bool)>,
+}
+
+impl Exclude {
+    fn new(patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern.trim())
+            .filter(|pattern| !pattern.is_empty() && !pattern.starts_with('#'))
+            .map(|pattern| {
+                let (pattern, negated) = match pattern.strip_prefix('!') {
+                    Some(pattern) => (pattern, true),
+                    None => (pattern, false),
+                };
+                let (pattern, dir_only) = match pattern.strip_suffix('/') {
+                    Some(pattern) => (pattern, true),
+                    None => (pattern, false),
+                };
+                // without a slash, it matches at any depth
+                let pattern = match pattern.strip_prefix('/') {
+                    Some(pattern) => pattern.to_string(),
+                    None if !pattern.contains('/') => format!("**/{}", pattern),
+                    None => pattern.to_string(),
+                };
+                (pattern, negated, dir_only)
+            })
+            .collect();
+        Exclude { patterns }
+    }
+
+    fn is_excluded(&self, relative: &str, is_dir: bool) -> bool {
+        self.patterns
+            .iter()
+            .rev()
+            .find(|(pattern, _, dir_only)| (is_dir || !dir_only) && glob_match(pattern, relative))
+            .is_some_and(|&(_, negated, _)| !negated)
+    }
+}
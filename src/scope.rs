@@ -0,0 +1,707 @@
+use crate::lexer::{collect_binding_names, collect_declaration_names, Lexer, Token, TokenKind};
+use crate::JsDialect;
+
+// the globals that tell the module system apart, so only their bindings are tracked
+pub const TRACKED_NAMES: [&str; 4] = ["require", "module", "exports", "define"];
+
+// keywords followed by parens that aren't function calls or methods
+const PAREN_KEYWORDS: [&str; 7] = ["if", "for", "while", "switch", "catch", "with", "function"];
+
+// lightweight scope analysis to know whether `require`, `module`, `exports` or `define` refer to
+// the globals. it handles var/let/const (including destructuring), function and class names,
+// params of functions, arrow functions and methods, catch clauses and import bindings.
+pub struct Scopes {
+    // byte starts of the tracked identifier tokens
+    identifiers: Vec<usize>,
+    // byte starts of tracked identifiers that aren't references, e.g. declarations, property keys
+    // and method names. sorted
+    non_references: Vec<usize>,
+    // byte ranges where each name is a local binding
+    bindings: Vec<(&'static str, Bindings)>,
+    // byte ranges of the outermost functions, from the params to the end of the body. sorted
+    functions: Vec<(usize, usize)>,
+    len: usize,
+}
+
+impl Scopes {
+    pub fn new(s: &str, dialect: JsDialect) -> Self {
+        Scopes::with_names(s, dialect, &TRACKED_NAMES)
+    }
+
+    // track other globals than the module system ones, e.g. helpers of compilers
+    pub fn with_names(s: &str, dialect: JsDialect, names: &'static [&'static str]) -> Self {
+        let tokens: Vec<Token> = Lexer::with_dialect(s, dialect)
+            .filter(|t| t.kind != TokenKind::Comment && t.kind != TokenKind::Jsx)
+            .collect();
+        let mut builder = ScopeBuilder::new(s, dialect, &tokens, names);
+        builder.build();
+
+        let identifiers = tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Identifier && names.contains(&t.text(s)))
+            .map(|t| t.start)
+            .collect();
+        let mut non_references = builder.non_references;
+        non_references.sort_unstable();
+
+        // functions are either nested or disjoint, keep the outermost
+        let mut all_functions = builder.functions;
+        all_functions.sort_unstable_by_key(|&(start, end)| (start, usize::MAX - end));
+        let mut functions: Vec<(usize, usize)> = Vec::new();
+        for range in all_functions {
+            if functions.last().is_none_or(|last| range.0 >= last.1) {
+                functions.push(range);
+            }
+        }
+
+        let bindings = names
+            .iter()
+            .map(|&name| {
+                let ranges = builder
+                    .bindings
+                    .iter()
+                    .filter(|&&(binding, _, _)| binding == name)
+                    .map(|&(_, start, end)| (start, end))
+                    .collect();
+                (name, Bindings::new(ranges))
+            })
+            .collect();
+
+        Scopes {
+            identifiers,
+            non_references,
+            bindings,
+            functions,
+            len: s.len(),
+        }
+    }
+
+    // whether the byte offset isn't within any function, e.g. for top-level await
+    pub fn is_top_level(&self, start: usize) -> bool {
+        let index = self.functions.partition_point(|&(from, _)| from <= start);
+        index == 0 || self.functions[index - 1].1 <= start
+    }
+
+    // the tracked names still declared at the end, e.g. in a function that isn't closed, with
+    // where their scope starts. 0 for the top level
+    pub fn open_bindings(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.bindings.iter().flat_map(move |(name, bindings)| {
+            bindings
+                .ranges
+                .iter()
+                .filter(move |&&(_, end)| end == self.len)
+                .map(move |&(start, _)| (*name, start))
+        })
+    }
+
+    // whether the identifier starting at the byte offset refers to the global `name`
+    pub fn is_global_reference(&self, name: &str, start: usize) -> bool {
+        self.identifiers.binary_search(&start).is_ok()
+            && self.non_references.binary_search(&start).is_err()
+            && !self
+                .bindings
+                .iter()
+                .any(|(binding, bindings)| *binding == name && bindings.contains(start))
+    }
+}
+
+// the byte ranges of the bindings of a name, sorted by start. they're usually nested or
+// disjoint, but unclosed brackets can make them overlap, so the furthest end so far is kept to
+// look up whether any range contains an offset with a binary search
+struct Bindings {
+    ranges: Vec<(usize, usize)>,
+    max_ends: Vec<usize>,
+}
+
+impl Bindings {
+    fn new(mut ranges: Vec<(usize, usize)>) -> Self {
+        ranges.sort_unstable();
+        let max_ends = ranges
+            .iter()
+            .scan(0, |max_end, &(_, end)| {
+                *max_end = end.max(*max_end);
+                Some(*max_end)
+            })
+            .collect();
+        Bindings { ranges, max_ends }
+    }
+
+    fn contains(&self, offset: usize) -> bool {
+        let index = self.ranges.partition_point(|&(start, _)| start <= offset);
+        index > 0 && self.max_ends[index - 1] > offset
+    }
+}
+
+// whether the { at `i` starts an object literal instead of a block, from the token before it
+fn is_expression_brace(s: &str, tokens: &[Token], i: usize) -> bool {
+    let Some(prev) = i.checked_sub(1).map(|p| tokens[p]) else {
+        return false;
+    };
+    match prev.kind {
+        TokenKind::Punctuator => !matches!(prev.text(s), ")" | "]" | "}" | "{" | ";" | "=>"),
+        TokenKind::Identifier => matches!(
+            prev.text(s),
+            "return"
+                | "yield"
+                | "await"
+                | "typeof"
+                | "void"
+                | "delete"
+                | "in"
+                | "of"
+                | "instanceof"
+                | "case"
+                | "throw"
+                | "default"
+        ),
+        // `${`
+        TokenKind::Template => prev.text(s).ends_with("${"),
+        _ => false,
+    }
+}
+
+struct ScopeBuilder<'a> {
+    s: &'a str,
+    dialect: JsDialect,
+    tokens: &'a [Token],
+    names: &'static [&'static str],
+    // the matching bracket of each bracket token
+    matching: Vec<Option<usize>>,
+    // the innermost { each token is in
+    parent_brace: Vec<Option<usize>>,
+    // whether each { is a class body or an object literal
+    is_member_body: Vec<bool>,
+    // whether each { is a function body, for `var`
+    is_function_body: Vec<bool>,
+    non_references: Vec<usize>,
+    bindings: Vec<(&'static str, usize, usize)>,
+    functions: Vec<(usize, usize)>,
+}
+
+impl<'a> ScopeBuilder<'a> {
+    fn new(
+        s: &'a str,
+        dialect: JsDialect,
+        tokens: &'a [Token],
+        names: &'static [&'static str],
+    ) -> Self {
+        let mut matching = vec![None; tokens.len()];
+        let mut parent_brace = vec![None; tokens.len()];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut braces: Vec<usize> = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            parent_brace[i] = braces.last().copied();
+            if token.kind != TokenKind::Punctuator {
+                continue;
+            }
+            match token.text(s) {
+                "(" | "[" => stack.push(i),
+                "{" => {
+                    stack.push(i);
+                    braces.push(i);
+                }
+                ")" | "]" | "}" => {
+                    if let Some(open) = stack.pop() {
+                        matching[open] = Some(i);
+                        matching[i] = Some(open);
+                        if tokens[open].text(s) == "{" {
+                            braces.pop();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // class bodies and object literals, the only places where `foo() {}` is a method
+        let mut is_member_body = vec![false; tokens.len()];
+        for (i, token) in tokens.iter().enumerate() {
+            match (token.kind, token.text(s)) {
+                (TokenKind::Punctuator, "{") => {
+                    // class bodies are already marked from the `class` keyword
+                    is_member_body[i] |= is_expression_brace(s, tokens, i);
+                }
+                (TokenKind::Identifier, "class")
+                    if i == 0 || !matches!(tokens[i - 1].text(s), "." | "?.") =>
+                {
+                    let mut j = i + 1;
+                    while let Some(t) = tokens.get(j) {
+                        if t.kind == TokenKind::Punctuator {
+                            match t.text(s) {
+                                "{" => {
+                                    is_member_body[j] = true;
+                                    break;
+                                }
+                                "(" | "[" => match matching[j] {
+                                    Some(close) => j = close,
+                                    None => break,
+                                },
+                                ";" | "}" | ")" | "]" => break,
+                                _ => {}
+                            }
+                        }
+                        j += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        ScopeBuilder {
+            s,
+            dialect,
+            tokens,
+            names,
+            matching,
+            parent_brace,
+            is_member_body,
+            is_function_body: vec![false; tokens.len()],
+            non_references: Vec::new(),
+            bindings: Vec::new(),
+            functions: Vec::new(),
+        }
+    }
+
+    fn text(&self, i: usize) -> &'a str {
+        self.tokens.get(i).map_or("", |t| t.text(self.s))
+    }
+
+    fn kind(&self, i: usize) -> Option<TokenKind> {
+        self.tokens.get(i).map(|t| t.kind)
+    }
+
+    fn is_punctuator(&self, i: usize, p: &str) -> bool {
+        self.kind(i) == Some(TokenKind::Punctuator) && self.text(i) == p
+    }
+
+    fn is_identifier(&self, i: usize) -> bool {
+        self.kind(i) == Some(TokenKind::Identifier)
+    }
+
+    fn prev_text(&self, i: usize) -> &'a str {
+        if i == 0 {
+            ""
+        } else {
+            self.text(i - 1)
+        }
+    }
+
+    // the byte range of the block starting at the { token, until the end of the file if unclosed
+    fn block_range(&self, brace: usize) -> (usize, usize) {
+        let end = self.matching[brace].map_or(self.s.len(), |close| self.tokens[close].end);
+        (self.tokens[brace].start, end)
+    }
+
+    fn enclosing_block_range(&self, i: usize) -> (usize, usize) {
+        match self.parent_brace.get(i).copied().flatten() {
+            Some(brace) => self.block_range(brace),
+            None => (0, self.s.len()),
+        }
+    }
+
+    fn enclosing_function_range(&self, i: usize) -> (usize, usize) {
+        let mut brace = self.parent_brace.get(i).copied().flatten();
+        while let Some(b) = brace {
+            if self.is_function_body[b] {
+                return self.block_range(b);
+            }
+            brace = self.parent_brace[b];
+        }
+        (0, self.s.len())
+    }
+
+    fn add_non_reference(&mut self, i: usize) {
+        if self.names.contains(&self.text(i)) {
+            self.non_references.push(self.tokens[i].start);
+        }
+    }
+
+    // the identifier at `i` is declared within the byte range
+    fn declare(&mut self, i: usize, range: (usize, usize)) {
+        self.add_non_reference(i);
+        if let Some(&name) = self.names.iter().find(|&&name| name == self.text(i)) {
+            self.bindings.push((name, range.0, range.1));
+        }
+    }
+
+    // the function body after the closing paren of the params, skipping typescript return types
+    fn find_body(&self, close_paren: usize) -> Option<usize> {
+        let next = close_paren + 1;
+        if self.is_punctuator(next, "{") {
+            return Some(next);
+        }
+        if self.dialect.is_ts() && self.is_punctuator(next, ":") {
+            let mut j = next + 1;
+            while let Some(kind) = self.kind(j) {
+                if kind == TokenKind::Punctuator {
+                    match self.text(j) {
+                        "{" => return Some(j),
+                        "(" | "[" => match self.matching[j] {
+                            Some(close) => j = close,
+                            None => return None,
+                        },
+                        ";" | "}" | ")" | "]" | "=" | "=>" | "," => return None,
+                        _ => {}
+                    }
+                }
+                j += 1;
+            }
+        }
+        None
+    }
+
+    // declare the params between the parens within the byte range
+    fn declare_params(&mut self, open: usize, close: usize, range: (usize, usize)) {
+        let mut j = open + 1;
+        while j < close {
+            // typescript parameter properties and rest params
+            while matches!(
+                self.text(j),
+                "private" | "public" | "protected" | "readonly" | "override" | "..."
+            ) && j + 1 < close
+                && !matches!(self.text(j + 1), "," | ":" | "=" | ")")
+            {
+                j += 1;
+            }
+            let mut names = Vec::new();
+            collect_binding_names(self.s, self.tokens, j, &mut names);
+            for name in names {
+                self.declare(name, range);
+            }
+            // skip the type annotation and default value until the next param
+            let mut k = j;
+            while k < close && !self.is_punctuator(k, ",") {
+                k = match self.matching[k] {
+                    Some(m) if m > k && self.kind(k) == Some(TokenKind::Punctuator) => m + 1,
+                    _ => k + 1,
+                };
+            }
+            j = k + 1;
+        }
+    }
+
+    // a function whose params are within the parens, declares them and returns the body range
+    fn declare_function(&mut self, open: usize) -> Option<(usize, usize)> {
+        let close = self.matching[open]?;
+        let body = self.find_body(close)?;
+        self.is_function_body[body] = true;
+        let range = (self.tokens[open].start, self.block_range(body).1);
+        self.functions.push(range);
+        self.declare_params(open, close, range);
+        Some(range)
+    }
+
+    // whether the token at `i` is where a statement starts, e.g. a function declaration instead
+    // of a function expression
+    fn is_statement_start(&self, i: usize) -> bool {
+        if i == 0 {
+            return true;
+        }
+        match self.kind(i - 1) {
+            // `)` and `]` can only be followed by a statement after a newline, e.g. `if (a)`
+            Some(TokenKind::Punctuator) => {
+                matches!(self.prev_text(i), ";" | "{" | "}" | ")" | "]")
+            }
+            Some(TokenKind::Identifier) => !matches!(
+                self.prev_text(i),
+                "return"
+                    | "typeof"
+                    | "void"
+                    | "delete"
+                    | "new"
+                    | "yield"
+                    | "await"
+                    | "in"
+                    | "of"
+                    | "instanceof"
+                    | "case"
+                    | "throw"
+                    | "extends"
+            ),
+            _ => true,
+        }
+    }
+
+    fn is_ambient(&self, i: usize) -> bool {
+        self.dialect.is_ts() && self.prev_text(i) == "declare"
+    }
+
+    fn build(&mut self) {
+        let mut i = 0;
+        while i < self.tokens.len() {
+            let kind = self.kind(i);
+            let is_property = matches!(self.prev_text(i), "." | "?.");
+            if kind == Some(TokenKind::Identifier) && is_property {
+                // foo.exports
+                self.add_non_reference(i);
+            } else if kind == Some(TokenKind::Identifier) {
+                match self.text(i) {
+                    "function" => self.visit_function(i),
+                    "class" => self.visit_class(i),
+                    "var" | "let" | "const" if self.is_declaration_keyword(i) => {
+                        self.visit_declaration(i)
+                    }
+                    "catch" if self.is_punctuator(i + 1, "(") => self.visit_catch(i),
+                    "import" if self.is_statement_start(i) => self.visit_import(i),
+                    _ => self.visit_identifier(i),
+                }
+            } else if self.is_punctuator(i, "=>") {
+                self.visit_arrow(i);
+            }
+            i += 1;
+        }
+    }
+
+    // `let` can be an identifier in sloppy mode, e.g. `let = 1`
+    fn is_declaration_keyword(&self, i: usize) -> bool {
+        self.is_identifier(i + 1)
+            || self.is_punctuator(i + 1, "{")
+            || self.is_punctuator(i + 1, "[")
+    }
+
+    fn visit_function(&mut self, i: usize) {
+        let mut j = i + 1;
+        if self.is_punctuator(j, "*") {
+            j += 1;
+        }
+        let name = if self.is_identifier(j) { Some(j) } else { None };
+        if name.is_some() {
+            j += 1;
+        }
+        // typescript generics, function foo<T>()
+        if self.dialect.is_ts() && self.is_punctuator(j, "<") {
+            while self.kind(j).is_some() && !self.is_punctuator(j, "(") {
+                j += 1;
+            }
+        }
+        if !self.is_punctuator(j, "(") {
+            return;
+        }
+
+        let is_ambient = self.is_ambient(i);
+        let is_declaration = if self.prev_text(i) == "async" {
+            self.is_statement_start(i - 1)
+        } else {
+            self.is_statement_start(i)
+        };
+        let range = self.declare_function(j);
+        if let Some(name) = name {
+            if is_ambient {
+                self.add_non_reference(name);
+            } else if is_declaration {
+                let range = self.enclosing_block_range(i);
+                self.declare(name, range);
+            } else if let Some(range) = range {
+                // function expression names are only visible within
+                self.declare(name, range);
+            } else {
+                self.add_non_reference(name);
+            }
+        }
+    }
+
+    fn visit_class(&mut self, i: usize) {
+        let name = i + 1;
+        if !self.is_identifier(name) || matches!(self.text(name), "extends" | "implements") {
+            return;
+        }
+        if self.is_ambient(i) {
+            self.add_non_reference(name);
+        } else if self.is_statement_start(i) {
+            let range = self.enclosing_block_range(i);
+            self.declare(name, range);
+        } else {
+            // class expression names are only visible within
+            let body = (name + 1..self.tokens.len()).find(|&j| self.is_punctuator(j, "{"));
+            match body {
+                Some(body) => {
+                    let range = (self.tokens[i].start, self.block_range(body).1);
+                    self.declare(name, range);
+                }
+                None => self.add_non_reference(name),
+            }
+        }
+    }
+
+    // var, let and const, including destructuring
+    fn visit_declaration(&mut self, i: usize) {
+        let is_ambient = self.is_ambient(i);
+        let range = if self.text(i) == "var" {
+            self.enclosing_function_range(i)
+        } else if i >= 2 && self.prev_text(i) == "(" && self.text(i - 2) == "for" {
+            // for (let foo of bar) {}, only within the loop
+            let end = match self.matching[i - 1] {
+                Some(close) if self.is_punctuator(close + 1, "{") => self.block_range(close + 1).1,
+                Some(close) => self.statement_end(close + 1),
+                None => self.enclosing_block_range(i).1,
+            };
+            (self.tokens[i - 1].start, end)
+        } else {
+            self.enclosing_block_range(i)
+        };
+
+        let mut names = Vec::new();
+        collect_declaration_names(self.s, self.tokens, i + 1, &mut names);
+        for name in names {
+            if is_ambient {
+                self.add_non_reference(name);
+            } else {
+                self.declare(name, range);
+            }
+        }
+    }
+
+    // the byte offset where the statement without braces starting at `i` ends, e.g. a loop body
+    fn statement_end(&self, i: usize) -> usize {
+        let mut depth: usize = 0;
+        let mut j = i;
+        while let Some(token) = self.tokens.get(j) {
+            let is_new_line = j > i && self.s[self.tokens[j - 1].end..token.start].contains('\n');
+            if depth == 0 && is_new_line {
+                return self.tokens[j - 1].end;
+            }
+            if token.kind == TokenKind::Punctuator {
+                match self.text(j) {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" if depth == 0 => return token.start,
+                    ")" | "]" | "}" => depth -= 1,
+                    ";" if depth == 0 => return token.end,
+                    _ => {}
+                }
+            }
+            j += 1;
+        }
+        self.s.len()
+    }
+
+    // catch (foo) {}
+    fn visit_catch(&mut self, i: usize) {
+        let open = i + 1;
+        let body = match self.matching[open] {
+            Some(close) if self.is_punctuator(close + 1, "{") => close + 1,
+            _ => return,
+        };
+        let range = self.block_range(body);
+        let mut names = Vec::new();
+        collect_binding_names(self.s, self.tokens, open + 1, &mut names);
+        for name in names {
+            self.declare(name, range);
+        }
+    }
+
+    // import foo, { bar as baz }, * as qux from 'foo'
+    fn visit_import(&mut self, i: usize) {
+        let mut j = i + 1;
+        while let Some(kind) = self.kind(j) {
+            match (kind, self.text(j)) {
+                (TokenKind::Identifier, "from") => break,
+                // the local name is the last one, e.g. `bar as baz`
+                (TokenKind::Identifier, _)
+                    if !self.is_identifier(j + 1) || self.text(j + 1) == "from" =>
+                {
+                    self.declare(j, (0, self.s.len()));
+                }
+                (TokenKind::Identifier | TokenKind::String, _) => {
+                    self.add_non_reference(j);
+                }
+                (TokenKind::Punctuator, "{" | "}" | "," | "*") => {}
+                _ => break,
+            }
+            j += 1;
+        }
+    }
+
+    // (foo) => {}, foo => foo, async (foo) => foo
+    fn visit_arrow(&mut self, arrow: usize) {
+        if arrow == 0 {
+            return;
+        }
+        let body = arrow + 1;
+        let close = self.find_arrow_params_end(arrow);
+        let start;
+        let params = if self.is_identifier(close) {
+            start = close;
+            None
+        } else if self.is_punctuator(close, ")") {
+            let open = match self.matching[close] {
+                Some(open) => open,
+                None => return,
+            };
+            start = open;
+            Some(open)
+        } else {
+            return;
+        };
+
+        let end = if self.is_punctuator(body, "{") {
+            self.is_function_body[body] = true;
+            self.block_range(body).1
+        } else {
+            // concise body, until the expression ends
+            let mut j = body;
+            let mut depth: usize = 0;
+            while let Some(kind) = self.kind(j) {
+                if kind == TokenKind::Punctuator {
+                    match self.text(j) {
+                        "(" | "[" | "{" => depth += 1,
+                        ")" | "]" | "}" if depth == 0 => break,
+                        ")" | "]" | "}" => depth -= 1,
+                        "," | ";" if depth == 0 => break,
+                        _ => {}
+                    }
+                }
+                j += 1;
+            }
+            self.tokens.get(j).map_or(self.s.len(), |t| t.start)
+        };
+
+        let range = (self.tokens[start].start, end);
+        self.functions.push(range);
+        match params {
+            Some(open) => self.declare_params(open, close, range),
+            None => self.declare(start, range),
+        }
+    }
+
+    // the token before `=>` that ends the params, skipping typescript return types, e.g.
+    // `(foo): Bar => foo`
+    fn find_arrow_params_end(&self, arrow: usize) -> usize {
+        if self.dialect.is_ts() && !self.is_punctuator(arrow - 1, ")") {
+            let mut j = arrow - 1;
+            while j > 0 {
+                match self.text(j) {
+                    ":" if self.is_punctuator(j - 1, ")") => return j - 1,
+                    ")" | "]" | "}" => match self.matching[j] {
+                        Some(open) if open > 0 => j = open,
+                        _ => break,
+                    },
+                    ";" | "," | "=" | "(" | "{" | "[" | "=>" | ":" => break,
+                    _ => {}
+                }
+                j -= 1;
+            }
+        }
+        arrow - 1
+    }
+
+    // methods `foo() {}` and property keys `{ foo: 1 }`
+    fn visit_identifier(&mut self, i: usize) {
+        if self.is_punctuator(i + 1, ":") && matches!(self.prev_text(i), "{" | ",") {
+            self.add_non_reference(i);
+            return;
+        }
+        let in_member_body = self.parent_brace[i].is_some_and(|brace| self.is_member_body[brace]);
+        if in_member_body
+            && self.is_punctuator(i + 1, "(")
+            && !PAREN_KEYWORDS.contains(&self.text(i))
+        {
+            // a call followed by a block on the next line isn't a method, e.g. `foo()\n{}`
+            let is_method =
+                self.matching[i + 1].is_some_and(|close| self.find_body(close).is_some());
+            if is_method {
+                self.add_non_reference(i);
+                self.declare_function(i + 1);
+            }
+        }
+    }
+}
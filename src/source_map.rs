@@ -0,0 +1,151 @@
+use crate::comment::parse_source_map_comment;
+use crate::position::{LineIndex, Span};
+use crate::walk::{WalkItemKind, Walker};
+use crate::JsDialect;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsSourceMapCommentKind {
+    // `//# sourceMappingURL=foo.js.map`
+    SourceMappingUrl,
+    // `//# sourceURL=foo.js`
+    SourceUrl,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsSourceMapComment {
+    pub kind: JsSourceMapCommentKind,
+    pub url: String,
+    // the decoded source map if `url` is an inline `data:application/json` uri
+    pub data: Option<String>,
+    // the whole comment
+    pub span: Span,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsSourceMapComments {
+    pub comments: Vec<JsSourceMapComment>,
+    // the source without the comments, if stripping was requested
+    pub code: Option<String>,
+}
+
+// find the source map comments at the end of the file. like browsers and node, comments before
+// other code are ignored, as they're usually from inlined dependencies
+pub fn parse_source_map_comments(s: &str, dialect: JsDialect, strip: bool) -> JsSourceMapComments {
+    let b = s.as_bytes();
+    let index = LineIndex::new(s);
+    let mut found = Vec::new();
+
+    for item in Walker::with_dialect(s, dialect) {
+        match item.kind {
+            WalkItemKind::LineComment => {
+                let text = &s[item.start + 2..item.end];
+                let comment = [
+                    (JsSourceMapCommentKind::SourceMappingUrl, "sourceMappingURL"),
+                    (JsSourceMapCommentKind::SourceUrl, "sourceURL"),
+                ]
+                .into_iter()
+                .find_map(|(kind, name)| Some((kind, parse_source_map_comment(text, name)?)));
+                if let Some((kind, url)) = comment {
+                    found.push((kind, url, item.start, item.end));
+                }
+            }
+            WalkItemKind::Code if b[item.start].is_ascii_whitespace() => {}
+            WalkItemKind::BlockComment | WalkItemKind::HtmlComment => {}
+            _ => found.clear(),
+        }
+    }
+
+    let code = strip.then(|| {
+        let mut code = String::with_capacity(s.len());
+        let mut last = 0;
+        for &(_, _, start, end) in &found {
+            code.push_str(&s[last..start]);
+            // remove its line break too
+            last = match b[end..] {
+                [b'\r', b'\n', ..] => end + 2,
+                [b'\n' | b'\r', ..] => end + 1,
+                _ => end,
+            };
+        }
+        code.push_str(&s[last..]);
+        code
+    });
+
+    JsSourceMapComments {
+        comments: found
+            .into_iter()
+            .map(|(kind, url, start, end)| JsSourceMapComment {
+                kind,
+                url: url.to_string(),
+                data: decode_json_data_uri(url),
+                span: index.span(start, end),
+            })
+            .collect(),
+        code,
+    }
+}
+
+// decode `data:application/json;base64,eyJ2...` or `data:application/json,{"v...`
+fn decode_json_data_uri(url: &str) -> Option<String> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let mut params = header.split(';');
+    let mime = params.next()?;
+    if mime != "application/json" && mime != "text/json" {
+        return None;
+    }
+    let bytes = if params.any(|param| param == "base64") {
+        decode_base64(data)?
+    } else {
+        decode_percent(data)?
+    };
+    String::from_utf8(bytes).ok()
+}
+
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in s.bytes().take_while(|&c| c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        // only the bits that aren't pushed yet are kept
+        buffer = ((buffer << 6) | value as u32) & 0xffff;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(bytes)
+}
+
+fn decode_percent(s: &str) -> Option<Vec<u8>> {
+    let b = s.as_bytes();
+    let mut bytes = Vec::with_capacity(b.len());
+    let mut i = 0;
+
+    while i < b.len() {
+        if b[i] == b'%' {
+            let hex = std::str::from_utf8(b.get(i + 1..i + 3)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            bytes.push(b[i]);
+            i += 1;
+        }
+    }
+
+    Some(bytes)
+}
@@ -0,0 +1,290 @@
+use crate::scope::{Scopes, TRACKED_NAMES};
+use crate::syntax::{
+    is_never_declared, scan_js_syntax, scan_js_syntax_with_scopes, JsSyntaxEvidenceKind,
+};
+use crate::walk::{WalkCallbackResult, WalkItemKind, Walker};
+use crate::{JsDialect, JsSyntax};
+use wasm_bindgen::prelude::*;
+
+// scan once this many bytes are walked and a line ends, so short lines aren't scanned one by one
+const MIN_PART_LEN: usize = 4096;
+// minified code can be a single line, then a part can also end at a `;`
+const MAX_PART_LEN: usize = 65536;
+// scanned bytes that are kept for the walker to look behind, e.g. for `return /re/`
+const LOOKBEHIND: usize = 64;
+
+// detect the syntax of a file that's read in chunks, without keeping all of it in memory.
+//
+// the walker keeps its state between chunks and only walks the new bytes. every few kilobytes,
+// at a line break, the walked part is scanned. a part can end within brackets, e.g. in a bundle
+// wrapped in a function, so the brackets that are still open are kept with the text before them,
+// e.g. `(function (module, exports) {`, and the tracked globals declared within them. they're
+// scanned again with the next part, so params and declarations still shadow. a `var` that's
+// declared after its use in an earlier part is missed.
+#[wasm_bindgen]
+pub struct JsSyntaxStream {
+    dialect: JsDialect,
+    // a few scanned bytes, the walked bytes that aren't scanned yet from `pending` on, and the
+    // bytes that aren't walked yet
+    buffer: Vec<u8>,
+    // the length of the buffer that's valid utf-8, without a character that's cut off
+    valid_len: usize,
+    pending: usize,
+    walker: Walker<'static>,
+    // buffer offsets of the brackets walked since `pending` that are still open
+    open: Vec<usize>,
+    // the brackets that were open when a part ended, from the outermost
+    context: Vec<OpenBracket>,
+    // tracked globals declared at the top level
+    shadowed: Vec<&'static str>,
+    is_esm: bool,
+    is_cjs: bool,
+}
+
+// a bracket that's still open after its part was scanned
+struct OpenBracket {
+    // the text since the previous open bracket or the start of the part, ending with the bracket
+    text: String,
+    // tracked globals declared in the block, for {
+    shadowed: Vec<&'static str>,
+}
+
+#[wasm_bindgen]
+impl JsSyntaxStream {
+    #[wasm_bindgen(constructor)]
+    pub fn new(dialect: JsDialect) -> Self {
+        JsSyntaxStream {
+            dialect,
+            buffer: Vec::new(),
+            valid_len: 0,
+            pending: 0,
+            walker: Walker::with_dialect("", dialect).suspend(),
+            open: Vec::new(),
+            context: Vec::new(),
+            shadowed: Vec::new(),
+            is_esm: false,
+            is_cjs: false,
+        }
+    }
+
+    // add the next chunk of utf-8 bytes, which can end in the middle of a character. returns
+    // true once both esm and cjs was found, then the rest of the file doesn't change the result
+    pub fn push(&mut self, chunk: &[u8]) -> bool {
+        if self.is_done() {
+            return true;
+        }
+        self.buffer.extend_from_slice(chunk);
+        self.valid_len = replace_invalid_utf8(&mut self.buffer, self.valid_len);
+        self.walk(false);
+        self.drain();
+        self.is_done()
+    }
+
+    // the syntax found so far
+    pub fn syntax(&self) -> JsSyntax {
+        JsSyntax::from_flags(self.is_esm, self.is_cjs)
+    }
+
+    // scan the rest of the buffer, as the file has ended
+    pub fn finish(mut self) -> JsSyntax {
+        if !self.is_done() {
+            // a character that's cut off at the end is invalid now
+            let rest = String::from_utf8_lossy(&self.buffer[self.valid_len..]).into_owned();
+            self.buffer.truncate(self.valid_len);
+            self.buffer.extend_from_slice(rest.as_bytes());
+            self.valid_len = self.buffer.len();
+            self.walk(true);
+        }
+        self.syntax()
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_esm && self.is_cjs
+    }
+
+    // walk the new bytes and scan the parts that end. unless the file has ended, the walker
+    // stops before an item that more bytes could change
+    fn walk(&mut self, is_end: bool) {
+        let buffer = std::mem::take(&mut self.buffer);
+        // the bytes are valid after `replace_invalid_utf8`
+        let s = std::str::from_utf8(&buffer[..self.valid_len]).unwrap_or("");
+        let b = s.as_bytes();
+        let empty = Walker::with_dialect("", self.dialect).suspend();
+        let mut walker = std::mem::replace(&mut self.walker, empty).resume(s);
+        // the end of the last line that's walked, to answer early with short input too
+        let mut last_line_end = 0;
+
+        while !self.is_done() {
+            let item = if is_end {
+                walker.next()
+            } else {
+                walker.next_complete()
+            };
+            let Some(item) = item else {
+                break;
+            };
+            if item.kind != WalkItemKind::Code || !walker.is_at_rest() {
+                continue;
+            }
+            let part_len = item.end - self.pending;
+            match b[item.start] {
+                b'{' | b'(' | b'[' => self.open.push(item.start),
+                b'}' | b')' | b']' if !self.open.is_empty() => {
+                    self.open.pop();
+                }
+                // the bracket closes the context, so the part ends before it
+                b'}' | b')' | b']' if !self.context.is_empty() => {
+                    self.scan(s, item.start);
+                    self.context.pop();
+                    self.pending = item.end;
+                }
+                b'\n' if part_len >= MIN_PART_LEN => self.scan(s, item.end),
+                b'\n' => last_line_end = item.end,
+                b';' if part_len >= MAX_PART_LEN => self.scan(s, item.end),
+                _ => {}
+            }
+        }
+        if is_end && !self.is_done() {
+            self.scan(s, s.len());
+        } else if last_line_end > self.pending && !self.is_done() {
+            self.scan(s, last_line_end);
+        }
+
+        walker.diagnostics.clear();
+        self.walker = walker.suspend();
+        self.buffer = buffer;
+    }
+
+    // scan the part from `pending` until `end` with the context before it
+    fn scan(&mut self, s: &str, end: usize) {
+        let part = &s[self.pending..end];
+        // declarations of the tracked names are kept for the next parts
+        let declares = TRACKED_NAMES
+            .iter()
+            .any(|name| !is_never_declared(part.as_bytes(), name));
+        // without the tracked names or `await`, the context doesn't change what the part refers
+        // to. typescript namespaces in the context change what counts though
+        let needs_context = declares
+            || self.dialect.is_ts()
+            || TRACKED_NAMES
+                .iter()
+                .chain(&["await"])
+                .any(|name| part.contains(name));
+
+        let mut text = String::new();
+        // where the blocks start in `text`, with their index in the context
+        let mut braces = Vec::new();
+        if needs_context {
+            let declare = |text: &mut String, names: &[&str]| {
+                for name in names {
+                    text.push_str("let ");
+                    text.push_str(name);
+                    text.push(';');
+                }
+            };
+            declare(&mut text, &self.shadowed);
+            for (index, bracket) in self.context.iter().enumerate() {
+                text.push_str(&bracket.text);
+                if bracket.text.ends_with('{') {
+                    braces.push((text.len() - 1, index));
+                    declare(&mut text, &bracket.shadowed);
+                }
+            }
+        }
+        let start = text.len();
+        text.push_str(part);
+
+        // brackets after the end stay in the next part
+        let split = self.open.partition_point(|&open| open < end);
+        let mut from = self.pending;
+        for open in self.open.drain(..split) {
+            if s.as_bytes()[open] == b'{' {
+                braces.push((start + open - self.pending, self.context.len()));
+            }
+            self.context.push(OpenBracket {
+                text: s[from..open + 1].to_string(),
+                shadowed: Vec::new(),
+            });
+            from = open + 1;
+        }
+        self.pending = end;
+
+        let (is_esm, is_cjs) = (&mut self.is_esm, &mut self.is_cjs);
+        let cb = |kind: JsSyntaxEvidenceKind, i, _| {
+            // the context was scanned with its own part
+            if i < start {
+                return WalkCallbackResult::Continue;
+            }
+            match kind.syntax() {
+                JsSyntax::ESM => *is_esm = true,
+                JsSyntax::CJS => *is_cjs = true,
+                _ => {}
+            }
+            if *is_esm && *is_cjs {
+                WalkCallbackResult::Break
+            } else {
+                WalkCallbackResult::Continue
+            }
+        };
+        if !declares {
+            scan_js_syntax(&text, self.dialect, cb);
+            return;
+        }
+        let scopes = Scopes::new(&text, self.dialect);
+        scan_js_syntax_with_scopes(&text, self.dialect, &scopes, cb);
+
+        for (name, scope_start) in scopes.open_bindings() {
+            let shadowed = if scope_start == 0 {
+                &mut self.shadowed
+            } else {
+                // the block it's declared in, or the body of the function it's a param of
+                match braces.iter().find(|&&(brace, _)| brace >= scope_start) {
+                    Some(&(_, index)) => &mut self.context[index].shadowed,
+                    None => continue,
+                }
+            };
+            if !shadowed.contains(&name) {
+                shadowed.push(name);
+            }
+        }
+    }
+
+    // drop the scanned bytes, except the ones the walker looks behind at
+    fn drain(&mut self) {
+        let mut keep = self.pending.saturating_sub(LOOKBEHIND);
+        while keep > 0 && self.buffer[keep] & 0xC0 == 0x80 {
+            keep -= 1;
+        }
+        // only move the buffer once it's worth it
+        if keep < MIN_PART_LEN {
+            return;
+        }
+        self.buffer.drain(..keep);
+        self.valid_len -= keep;
+        self.pending -= keep;
+        self.walker.i -= keep;
+        for open in &mut self.open {
+            *open -= keep;
+        }
+    }
+}
+
+// replace invalid utf-8 sequences from `start` on with U+FFFD, like `String::from_utf8_lossy`.
+// returns the length of the valid bytes, excluding a character that's cut off at the end
+fn replace_invalid_utf8(buffer: &mut Vec<u8>, mut start: usize) -> usize {
+    loop {
+        match std::str::from_utf8(&buffer[start..]) {
+            Ok(_) => return buffer.len(),
+            Err(err) => {
+                let invalid = start + err.valid_up_to();
+                match err.error_len() {
+                    Some(len) => {
+                        buffer.splice(invalid..invalid + len, "\u{FFFD}".bytes());
+                        start = invalid + 3;
+                    }
+                    None => return invalid,
+                }
+            }
+        }
+    }
+}
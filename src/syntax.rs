@@ -0,0 +1,593 @@
+use crate::diagnostic::{JsDiagnostic, JsDiagnosticKind};
+use crate::format::{JsFormat, JsFormatFlags};
+use crate::position::{LineIndex, Span};
+use crate::scope::{Scopes, TRACKED_NAMES};
+use crate::utils::{
+    get_identifier_end, get_line_snippet, get_nearest_non_whitespace_index_left,
+    is_export_identifier, is_exports_identifier, is_import_identifier, is_meta_identifier,
+    is_module_identifier, is_require_identifier, is_word_at, is_word_ending_at,
+    skip_whitespace_right,
+};
+use crate::walk::{walk, WalkCallbackResult};
+use crate::{JsDialect, JsSyntax};
+use std::cell::OnceCell;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsSyntaxEvidenceKind {
+    // esm
+    Import,
+    ImportMeta,
+    Export,
+    // `await` outside of functions
+    TopLevelAwait,
+    // cjs
+    Require,
+    Module,
+    Exports,
+    // typescript, cjs
+    TsImportEquals,
+    TsExportEquals,
+    // typescript, erased during compilation so neither esm nor cjs
+    TsTypeImport,
+    TsTypeExport,
+    // amd, `define(...)` or `define.amd`. neither esm nor cjs, see `JsFormat`
+    AmdDefine,
+    // systemjs, `System.register(...)`. neither esm nor cjs, see `JsFormat`
+    SystemRegister,
+}
+
+impl JsSyntaxEvidenceKind {
+    pub fn syntax(&self) -> JsSyntax {
+        match self {
+            JsSyntaxEvidenceKind::Import
+            | JsSyntaxEvidenceKind::ImportMeta
+            | JsSyntaxEvidenceKind::Export
+            | JsSyntaxEvidenceKind::TopLevelAwait => JsSyntax::ESM,
+            JsSyntaxEvidenceKind::Require
+            | JsSyntaxEvidenceKind::Module
+            | JsSyntaxEvidenceKind::Exports
+            | JsSyntaxEvidenceKind::TsImportEquals
+            | JsSyntaxEvidenceKind::TsExportEquals => JsSyntax::CJS,
+            JsSyntaxEvidenceKind::TsTypeImport
+            | JsSyntaxEvidenceKind::TsTypeExport
+            | JsSyntaxEvidenceKind::AmdDefine
+            | JsSyntaxEvidenceKind::SystemRegister => JsSyntax::Unknown,
+        }
+    }
+}
+
+// an occurrence that made the file look esm or cjs
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsSyntaxEvidence {
+    pub kind: JsSyntaxEvidenceKind,
+    pub span: Span,
+    // the trimmed line the evidence is on
+    pub snippet: String,
+}
+
+// how much the syntax can be trusted
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsConfidence {
+    // found import, export, module or exports without any guessing
+    High,
+    // the file is mixed, or only has `require`, which bundlers also provide to esm
+    Medium,
+    // nothing was found, or the heuristics had to guess, so a full parser may disagree
+    Low,
+}
+
+impl JsConfidence {
+    fn new(syntax: JsSyntax, kinds: &[JsSyntaxEvidenceKind], ambiguous: bool) -> Self {
+        let is_require_only = kinds
+            .iter()
+            .filter(|kind| kind.syntax() == JsSyntax::CJS)
+            .all(|&kind| kind == JsSyntaxEvidenceKind::Require);
+        match syntax {
+            _ if ambiguous => JsConfidence::Low,
+            JsSyntax::Unknown => JsConfidence::Low,
+            JsSyntax::Mixed => JsConfidence::Medium,
+            JsSyntax::CJS if is_require_only => JsConfidence::Medium,
+            _ => JsConfidence::High,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsSyntaxGuess {
+    pub syntax: JsSyntax,
+    pub confidence: JsConfidence,
+    // whether a / was guessed to be a division or regex, braces were unbalanced, or something
+    // was unterminated
+    pub ambiguous: bool,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsSyntaxAnalysis {
+    pub syntax: JsSyntax,
+    pub confidence: JsConfidence,
+    pub ambiguous: bool,
+    pub format: JsFormat,
+    pub evidence: Vec<JsSyntaxEvidence>,
+    // unterminated strings, comments, template literals and regexes, and the ambiguous slashes
+    // and unbalanced braces the heuristics had to guess at, see `JsDiagnosticKind::is_ambiguity`
+    pub diagnostics: Vec<JsDiagnostic>,
+}
+
+impl JsSyntaxEvidence {
+    fn new(
+        s: &str,
+        index: &LineIndex,
+        kind: JsSyntaxEvidenceKind,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        JsSyntaxEvidence {
+            kind,
+            span: index.span(start, end),
+            snippet: get_line_snippet(s, start),
+        }
+    }
+}
+
+pub fn guess_js_syntax_with_confidence(s: &str, dialect: JsDialect) -> JsSyntaxGuess {
+    let mut kinds = Vec::new();
+    let diagnostics = scan_js_syntax(s, dialect, |kind, _, _| {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+        WalkCallbackResult::Continue
+    });
+
+    let is_esm = kinds.iter().any(|kind| kind.syntax() == JsSyntax::ESM);
+    let is_cjs = kinds.iter().any(|kind| kind.syntax() == JsSyntax::CJS);
+    let syntax = JsSyntax::from_flags(is_esm, is_cjs);
+    let ambiguous = !diagnostics.is_empty();
+    JsSyntaxGuess {
+        syntax,
+        confidence: JsConfidence::new(syntax, &kinds, ambiguous),
+        ambiguous,
+    }
+}
+
+pub fn analyze_js_syntax(s: &str, dialect: JsDialect) -> JsSyntaxAnalysis {
+    let index = LineIndex::new(s);
+    let mut evidence = Vec::new();
+    let mut is_esm = false;
+    let mut is_cjs = false;
+    let mut format_flags = JsFormatFlags::default();
+
+    let diagnostics = scan_js_syntax(s, dialect, |kind, start, end| {
+        match kind.syntax() {
+            JsSyntax::ESM => is_esm = true,
+            JsSyntax::CJS => is_cjs = true,
+            _ => {}
+        }
+        format_flags.add(kind);
+        evidence.push(JsSyntaxEvidence::new(s, &index, kind, start, end));
+        WalkCallbackResult::Continue
+    });
+
+    let syntax = JsSyntax::from_flags(is_esm, is_cjs);
+    let kinds: Vec<_> = evidence.iter().map(|e| e.kind).collect();
+    let ambiguous = !diagnostics.is_empty();
+    JsSyntaxAnalysis {
+        syntax,
+        confidence: JsConfidence::new(syntax, &kinds, ambiguous),
+        ambiguous,
+        format: format_flags.format(s, dialect),
+        evidence,
+        diagnostics: diagnostics
+            .into_iter()
+            .map(|(kind, start, end)| JsDiagnostic::new(&index, kind, start, end))
+            .collect(),
+    }
+}
+
+// walk the code and report every esm and cjs occurrence with its byte range.
+// return `Break` from the callback to stop early. returns the diagnostics of the scanned part.
+pub fn scan_js_syntax<F>(
+    s: &str,
+    dialect: JsDialect,
+    cb: F,
+) -> Vec<(JsDiagnosticKind, usize, usize)>
+where
+    F: FnMut(JsSyntaxEvidenceKind, usize, usize) -> WalkCallbackResult,
+{
+    // shadowed `require`, `module`, `exports` and `define`. lexing the whole file is only needed
+    // once one of them or `await` shows up, so esm files and early breaks skip it
+    let scopes = OnceCell::new();
+    scan(
+        s,
+        dialect,
+        || scopes.get_or_init(|| Scopes::new(s, dialect)),
+        cb,
+    )
+}
+
+// same as `scan_js_syntax`, with the scopes of `s` already built
+pub fn scan_js_syntax_with_scopes<F>(
+    s: &str,
+    dialect: JsDialect,
+    scopes: &Scopes,
+    cb: F,
+) -> Vec<(JsDiagnosticKind, usize, usize)>
+where
+    F: FnMut(JsSyntaxEvidenceKind, usize, usize) -> WalkCallbackResult,
+{
+    scan(s, dialect, || scopes, cb)
+}
+
+fn scan<'a, G, F>(
+    s: &str,
+    dialect: JsDialect,
+    scopes: G,
+    mut cb: F,
+) -> Vec<(JsDiagnosticKind, usize, usize)>
+where
+    G: Fn() -> &'a Scopes,
+    F: FnMut(JsSyntaxEvidenceKind, usize, usize) -> WalkCallbackResult,
+{
+    // default depth is 0, every open brace increments, closing brace decrements.
+    // this happens for JS objects too but for us, it's good enough
+    let mut scope_depth: usize = 0;
+    // } without a matching {
+    let mut unbalanced_braces = Vec::new();
+    // unclosed braces are only known once the whole file is scanned
+    let mut is_stopped = false;
+    let mut cb = |kind, start, end| {
+        let result = cb(kind, start, end);
+        is_stopped |= result == WalkCallbackResult::Break;
+        result
+    };
+    // `require('foo')` and `module.exports` are global when the name is never declared, which
+    // is cheap to check, so mixed files can break before the scopes are built
+    let never_declared: [OnceCell<bool>; 4] = Default::default();
+    let is_global_reference = |b: &[u8], name: &str, i: usize| {
+        let index = TRACKED_NAMES.iter().position(|&v| v == name).unwrap_or(0);
+        (!is_property_access(b, i)
+            && is_plain_use(b, i, name.len())
+            && *never_declared[index].get_or_init(|| is_never_declared(b, name)))
+            || scopes().is_global_reference(name, i)
+    };
+    // typescript only. imports and exports in namespaces and `declare module` blocks don't
+    // belong to the file
+    let mut namespace_depth = usize::MAX;
+
+    let mut diagnostics = walk(s, dialect, |b, i, c| {
+        if dialect.is_ts() {
+            if scope_depth < namespace_depth && is_namespace_declaration(b, *i) {
+                // the namespace's { is next
+                namespace_depth = scope_depth + 1;
+                return WalkCallbackResult::Continue;
+            }
+
+            if scope_depth < namespace_depth {
+                if let Some((kind, end)) = get_ts_module_form(b, *i) {
+                    let result = match kind {
+                        Some(kind) => cb(kind, *i, end),
+                        None => WalkCallbackResult::Continue,
+                    };
+                    *i = end - 1;
+                    return result;
+                }
+            }
+        }
+
+        // esm specific detection
+
+        // top-level import
+        if scope_depth < namespace_depth
+            && is_import_identifier(b, *i)
+            && !is_property_access(b, *i)
+        {
+            let next = skip_whitespace_right(b, *i + 6);
+            let mut result = WalkCallbackResult::Continue;
+            match b.get(next) {
+                // import.meta, import . meta
+                Some(b'.') => {
+                    let meta = skip_whitespace_right(b, next + 1);
+                    if is_meta_identifier(b, meta) {
+                        result = cb(JsSyntaxEvidenceKind::ImportMeta, *i, meta + 4);
+                        *i = meta + 3;
+                        return result;
+                    }
+                }
+                // import('foo') is also available in cjs
+                Some(b'(') => {}
+                // import foo from 'foo', import { foo } from 'foo', import * as foo from 'foo',
+                // import 'foo', including across lines
+                Some(&v) if is_expression_start(v) => {
+                    result = cb(JsSyntaxEvidenceKind::Import, *i, *i + 6);
+                }
+                _ => {}
+            }
+            *i += 5;
+            return result;
+        }
+
+        // top-level export
+        if scope_depth < namespace_depth
+            && is_export_identifier(b, *i)
+            && !is_property_access(b, *i)
+        {
+            // not `{ export: 1 }` or `foo.export = 1`
+            let next = skip_whitespace_right(b, *i + 6);
+            let mut result = WalkCallbackResult::Continue;
+            if b.get(next)
+                .is_some_and(|&v| is_expression_start(v) && v != b'\'' && v != b'"')
+            {
+                result = cb(JsSyntaxEvidenceKind::Export, *i, *i + 6);
+            }
+            *i += 5;
+            return result;
+        }
+
+        // top-level await
+        if scope_depth < namespace_depth
+            && is_word_at(b, *i, b"await")
+            && !is_property_access(b, *i)
+            && scopes().is_top_level(*i)
+        {
+            let next = skip_whitespace_right(b, *i + 5);
+            let mut result = WalkCallbackResult::Continue;
+            // not `await = 1` or `await;` in scripts where it's an identifier
+            if is_await_operand_start(b, next) {
+                result = cb(JsSyntaxEvidenceKind::TopLevelAwait, *i, *i + 5);
+            }
+            *i += 4;
+            return result;
+        }
+
+        // cjs specific detection
+
+        // track scope depth
+        if c == b'{' {
+            scope_depth += 1;
+        } else if c == b'}' {
+            if scope_depth == 0 {
+                unbalanced_braces.push((JsDiagnosticKind::UnbalancedBrace, *i, *i + 1));
+            }
+            scope_depth = scope_depth.saturating_sub(1);
+            if scope_depth < namespace_depth {
+                namespace_depth = usize::MAX;
+            }
+        }
+
+        // require reference
+        if is_require_identifier(b, *i) {
+            let mut result = WalkCallbackResult::Continue;
+            if is_global_reference(b, "require", *i) {
+                result = cb(JsSyntaxEvidenceKind::Require, *i, *i + 7);
+            }
+            *i += 7;
+            return result;
+        }
+
+        // module reference
+        if is_module_identifier(b, *i) {
+            let mut result = WalkCallbackResult::Continue;
+            if is_global_reference(b, "module", *i) {
+                result = cb(JsSyntaxEvidenceKind::Module, *i, *i + 6);
+            }
+            *i += 6;
+            return result;
+        }
+
+        // exports reference
+        if is_exports_identifier(b, *i) {
+            let mut result = WalkCallbackResult::Continue;
+            if is_global_reference(b, "exports", *i) {
+                result = cb(JsSyntaxEvidenceKind::Exports, *i, *i + 7);
+            }
+            *i += 7;
+            return result;
+        }
+
+        // amd define reference
+        if is_word_at(b, *i, b"define") {
+            let mut result = WalkCallbackResult::Continue;
+            if !is_property_access(b, *i) && is_global_reference(b, "define", *i) {
+                let next = skip_whitespace_right(b, *i + 6);
+                // define([...], factory), or `typeof define === 'function' && define.amd`
+                let is_call = b.get(next) == Some(&b'(');
+                let is_amd = b.get(next) == Some(&b'.')
+                    && is_word_at(b, skip_whitespace_right(b, next + 1), b"amd");
+                if is_call || is_amd {
+                    result = cb(JsSyntaxEvidenceKind::AmdDefine, *i, *i + 6);
+                }
+            }
+            *i += 6;
+            return result;
+        }
+
+        // systemjs register
+        if is_word_at(b, *i, b"System") && !is_property_access(b, *i) {
+            let dot = skip_whitespace_right(b, *i + 6);
+            let register = skip_whitespace_right(b, dot + 1);
+            if b.get(dot) == Some(&b'.')
+                && is_word_at(b, register, b"register")
+                && b.get(skip_whitespace_right(b, register + 8)) == Some(&b'(')
+            {
+                let result = cb(JsSyntaxEvidenceKind::SystemRegister, *i, register + 8);
+                *i = register + 7;
+                return result;
+            }
+        }
+
+        WalkCallbackResult::Continue
+    });
+
+    if !is_stopped && scope_depth > 0 {
+        diagnostics.push((JsDiagnosticKind::UnbalancedBrace, s.len(), s.len()));
+    }
+    diagnostics.extend(unbalanced_braces);
+    diagnostics.sort_by_key(|v| v.1);
+    diagnostics
+}
+
+// whether the byte can start an import clause, export declaration or await operand
+fn is_expression_start(v: u8) -> bool {
+    v.is_ascii_alphabetic()
+        || v >= 0x80
+        || matches!(v, b'_' | b'$' | b'{' | b'*' | b'\'' | b'"' | b'`')
+}
+
+// whether an await operand can start at `i`, e.g. `await 1`, `await -x` or `await (x)`, but not
+// `await++`, `await != x` or `await -= 1` where `await` is an identifier
+fn is_await_operand_start(b: &[u8], i: usize) -> bool {
+    match b.get(i) {
+        Some(&v) if is_expression_start(v) || v.is_ascii_digit() => true,
+        Some(b'(' | b'[' | b'~') => true,
+        Some(&v @ (b'-' | b'+')) => !matches!(b.get(i + 1), Some(&n) if n == v || n == b'='),
+        Some(b'!') => b.get(i + 1) != Some(&b'='),
+        _ => false,
+    }
+}
+
+// whether the identifier at `i` is used in a way that can't declare it, e.g. `require('foo')`,
+// `module.exports` or `require.resolve`, unlike `function require() {}` or `{ require() {} }`
+fn is_plain_use(b: &[u8], i: usize, len: usize) -> bool {
+    let next = skip_whitespace_right(b, i + len);
+    match b.get(next) {
+        Some(b'.') => true,
+        Some(b'(') => matches!(
+            b.get(skip_whitespace_right(b, next + 1)),
+            Some(b'\'' | b'"' | b'`')
+        ),
+        _ => false,
+    }
+}
+
+// whether every occurrence of the name, including in strings and comments, is a property or a
+// plain use, so nothing can shadow it
+pub fn is_never_declared(b: &[u8], name: &str) -> bool {
+    let name = name.as_bytes();
+    let mut i = 0;
+    while let Some(pos) = b[i..].iter().position(|&v| v == name[0]) {
+        i += pos;
+        if is_word_at(b, i, name) && !is_property_access(b, i) && !is_plain_use(b, i, name.len()) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// whether the identifier at `i` is a property, e.g. `foo.define` or `_define`
+fn is_property_access(b: &[u8], i: usize) -> bool {
+    if i > 0 && (b[i - 1] == b'_' || b[i - 1] == b'$') {
+        return true;
+    }
+    i > 0 && b[get_nearest_non_whitespace_index_left(b, i)] == b'.'
+}
+
+// typescript, `namespace Foo {`, `module Foo {`, `declare module 'foo' {` or `declare global {`
+fn is_namespace_declaration(b: &[u8], i: usize) -> bool {
+    let name_start = if is_word_at(b, i, b"namespace") {
+        skip_whitespace_right(b, i + 9)
+    } else if is_word_at(b, i, b"module") {
+        skip_whitespace_right(b, i + 6)
+    } else if is_word_at(b, i, b"global") {
+        let left = get_nearest_non_whitespace_index_left(b, i);
+        if !is_word_ending_at(b, left, b"declare") {
+            return false;
+        }
+        return b.get(skip_whitespace_right(b, i + 6)) == Some(&b'{');
+    } else {
+        return false;
+    };
+
+    let name_end = match b.get(name_start) {
+        Some(b'\'' | b'"') => match b[name_start + 1..].iter().position(|&v| v == b[name_start]) {
+            Some(pos) => name_start + 1 + pos + 1,
+            None => return false,
+        },
+        Some(v) if v.is_ascii_alphabetic() || *v == b'_' || *v == b'$' => {
+            // namespace Foo.Bar {
+            let mut end = get_identifier_end(b, name_start);
+            while b.get(end) == Some(&b'.') {
+                end = get_identifier_end(b, end + 1);
+            }
+            end
+        }
+        _ => return false,
+    };
+    b.get(skip_whitespace_right(b, name_end)) == Some(&b'{')
+}
+
+// typescript specific module forms at `i`, returns the evidence kind and where it ends. the kind is
+// `None` for forms that aren't evidence of anything, e.g. `import Foo = Bar.Baz`
+fn get_ts_module_form(b: &[u8], i: usize) -> Option<(Option<JsSyntaxEvidenceKind>, usize)> {
+    if is_import_identifier(b, i) {
+        let next = skip_whitespace_right(b, i + 6);
+
+        // import type { Foo } from 'foo', but not import type from 'foo'
+        if is_word_at(b, next, b"type") {
+            let after_type = skip_whitespace_right(b, next + 4);
+            let is_type_import = match b.get(after_type) {
+                Some(b'{' | b'*') => true,
+                Some(_) => {
+                    !is_word_at(b, after_type, b"from")
+                        && get_identifier_end(b, after_type) > after_type
+                }
+                None => false,
+            };
+            if is_type_import {
+                return Some((Some(JsSyntaxEvidenceKind::TsTypeImport), next + 4));
+            }
+        }
+
+        // import foo = require('foo')
+        let name_end = get_identifier_end(b, next);
+        if name_end > next {
+            let equals = skip_whitespace_right(b, name_end);
+            if b.get(equals) == Some(&b'=') && !matches!(b.get(equals + 1), Some(b'=' | b'>')) {
+                let value = skip_whitespace_right(b, equals + 1);
+                if is_require_identifier(b, value) {
+                    return Some((Some(JsSyntaxEvidenceKind::TsImportEquals), value + 7));
+                }
+                // import Foo = Bar.Baz
+                return Some((None, value));
+            }
+        }
+        return None;
+    }
+
+    if is_export_identifier(b, i) {
+        let next = skip_whitespace_right(b, i + 6);
+
+        // export = foo
+        if b.get(next) == Some(&b'=') && !matches!(b.get(next + 1), Some(b'=' | b'>')) {
+            return Some((Some(JsSyntaxEvidenceKind::TsExportEquals), next + 1));
+        }
+
+        // export import foo = require('foo')
+        if let Some((Some(JsSyntaxEvidenceKind::TsImportEquals), end)) = get_ts_module_form(b, next)
+        {
+            return Some((Some(JsSyntaxEvidenceKind::TsImportEquals), end));
+        }
+
+        // export interface Foo {}, export declare const foo: string
+        if is_word_at(b, next, b"interface") || is_word_at(b, next, b"declare") {
+            return Some((Some(JsSyntaxEvidenceKind::TsTypeExport), next));
+        }
+
+        // export type Foo = string, export type { Foo }, export type * from 'foo'
+        if is_word_at(b, next, b"type") {
+            let after_type = skip_whitespace_right(b, next + 4);
+            if matches!(b.get(after_type), Some(b'{' | b'*'))
+                || get_identifier_end(b, after_type) > after_type
+            {
+                return Some((Some(JsSyntaxEvidenceKind::TsTypeExport), next + 4));
+            }
+        }
+    }
+
+    None
+}
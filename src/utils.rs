@@ -1,20 +1,14 @@
-// safe index (shorten to not be annoying)
-pub fn si(i: usize, b: &[u8]) -> usize {
-  i.min(b.len() - 1)
-}
+use std::iter::Peekable;
+use std::str::Chars;
 
 // make sure things aren't escaped by backtracking the number of backslashes.
 // we consider escaped if has an odd number of backslashes.
 pub fn is_backslash_escaped(full_str: &[u8], char_index: usize) -> bool {
-  let mut prev_iter_pos = char_index - 1;
-  while full_str[prev_iter_pos] == b'\\' {
-      if prev_iter_pos > 0 {
-          prev_iter_pos -= 1;
-      } else {
-          break;
-      }
-  }
-  let backslash_num = char_index - prev_iter_pos + 1;
+  let backslash_num = full_str[..char_index.min(full_str.len())]
+      .iter()
+      .rev()
+      .take_while(|&&v| v == b'\\')
+      .count();
   backslash_num % 2 == 1
 }
 
@@ -25,196 +19,99 @@ pub fn is_word_bounded(
   identifier_start_index: usize,
   identifier_end_index: usize,
 ) -> bool {
-  let left_bounded = identifier_start_index <= 0
-      || !full_str[identifier_start_index - 1].is_ascii_alphanumeric();
+  if full_str.is_empty() {
+      return false;
+  }
+  let left_bounded = identifier_start_index == 0
+      || !full_str
+          .get(identifier_start_index - 1)
+          .is_some_and(u8::is_ascii_alphanumeric);
   let right_bounded = identifier_end_index >= full_str.len()
       || !full_str[identifier_end_index].is_ascii_alphanumeric();
   left_bounded && right_bounded
 }
 
-// walks to the left until a non-whitespace character is found.
-// return 0 if out of string bounds
-pub fn get_nearest_non_whitespace_index_left(full_str: &[u8], char_index: usize) -> usize {
-  if char_index <= 0 {
-      return 0;
+// whether the word starting at `char_index` is `word`
+pub fn is_word_at(full_str: &[u8], char_index: usize, word: &[u8]) -> bool {
+  full_str[char_index.min(full_str.len())..].starts_with(word)
+      && is_word_bounded(full_str, char_index, char_index + word.len())
+}
+
+// whether the word ending at `char_index` (inclusive) is `word`
+pub fn is_word_ending_at(full_str: &[u8], char_index: usize, word: &[u8]) -> bool {
+  char_index + 1 >= word.len() && is_word_at(full_str, char_index + 1 - word.len(), word)
+}
+
+// the index after the identifier starting at `char_index`
+pub fn get_identifier_end(full_str: &[u8], char_index: usize) -> usize {
+  let mut i = char_index;
+  while i < full_str.len()
+      && (full_str[i].is_ascii_alphanumeric()
+          || full_str[i] == b'_'
+          || full_str[i] == b'$'
+          || full_str[i] >= 0x80)
+  {
+      i += 1;
   }
+  i
+}
+
+// walks to the right until a non-whitespace character is found, starting from `char_index`.
+// return string length if out of string bounds
+pub fn skip_whitespace_right(full_str: &[u8], char_index: usize) -> usize {
   let mut i = char_index;
-  while i > 0 {
-      i -= 1;
-      if !full_str[i].is_ascii_whitespace() {
-          break;
-      }
+  while i < full_str.len() && full_str[i].is_ascii_whitespace() {
+      i += 1;
   }
   i
 }
 
-// walks to the right until a non-whitespace character is found.
-// return string last index if out of string bounds
-pub fn get_nearest_non_whitespace_index_right(full_str: &[u8], char_index: usize) -> usize {
-  if char_index >= full_str.len() - 1 {
-      return full_str.len() - 1;
+// walks to the left until a non-whitespace character is found.
+// return 0 if out of string bounds
+pub fn get_nearest_non_whitespace_index_left(full_str: &[u8], char_index: usize) -> usize {
+  if char_index == 0 {
+      return 0;
   }
-  let mut i = char_index;
-  loop {
+  let mut i = char_index.min(full_str.len());
+  while i > 0 {
+      i -= 1;
       if !full_str[i].is_ascii_whitespace() {
           break;
       }
-      if i < full_str.len() - 1 {
-          i += 1;
-      } else {
-          break;
-      }
   }
   i
 }
 
 pub fn is_import_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'i'
-      && full_str[si(iter_index + 1, full_str)] == b'm'
-      && full_str[si(iter_index + 2, full_str)] == b'p'
-      && full_str[si(iter_index + 3, full_str)] == b'o'
-      && full_str[si(iter_index + 4, full_str)] == b'r'
-      && full_str[si(iter_index + 5, full_str)] == b't'
-      && is_word_bounded(&full_str, iter_index, iter_index + 6)
+  is_word_at(full_str, iter_index, b"import")
 }
 
 pub fn is_meta_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'm'
-      && full_str[si(iter_index + 1, full_str)] == b'e'
-      && full_str[si(iter_index + 2, full_str)] == b't'
-      && full_str[si(iter_index + 3, full_str)] == b'a'
-      && is_word_bounded(&full_str, iter_index, iter_index + 4)
+  is_word_at(full_str, iter_index, b"meta")
 }
 
 pub fn is_export_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'e'
-      && full_str[si(iter_index + 1, full_str)] == b'x'
-      && full_str[si(iter_index + 2, full_str)] == b'p'
-      && full_str[si(iter_index + 3, full_str)] == b'o'
-      && full_str[si(iter_index + 4, full_str)] == b'r'
-      && full_str[si(iter_index + 5, full_str)] == b't'
-      && is_word_bounded(&full_str, iter_index, iter_index + 6)
+  is_word_at(full_str, iter_index, b"export")
 }
 
 pub fn is_require_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'r'
-      && full_str[si(iter_index + 1, full_str)] == b'e'
-      && full_str[si(iter_index + 2, full_str)] == b'q'
-      && full_str[si(iter_index + 3, full_str)] == b'u'
-      && full_str[si(iter_index + 4, full_str)] == b'i'
-      && full_str[si(iter_index + 5, full_str)] == b'r'
-      && full_str[si(iter_index + 6, full_str)] == b'e'
-      && is_word_bounded(full_str, iter_index, iter_index + 7)
+  is_word_at(full_str, iter_index, b"require")
 }
 
 pub fn is_module_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'm'
-      && full_str[si(iter_index + 1, full_str)] == b'o'
-      && full_str[si(iter_index + 2, full_str)] == b'd'
-      && full_str[si(iter_index + 3, full_str)] == b'u'
-      && full_str[si(iter_index + 4, full_str)] == b'l'
-      && full_str[si(iter_index + 5, full_str)] == b'e'
-      && is_word_bounded(full_str, iter_index, iter_index + 6)
+  is_word_at(full_str, iter_index, b"module")
 }
 
 pub fn is_exports_identifier(full_str: &[u8], iter_index: usize) -> bool {
-  full_str[iter_index] == b'e'
-      && full_str[si(iter_index + 1, full_str)] == b'x'
-      && full_str[si(iter_index + 2, full_str)] == b'p'
-      && full_str[si(iter_index + 3, full_str)] == b'o'
-      && full_str[si(iter_index + 4, full_str)] == b'r'
-      && full_str[si(iter_index + 5, full_str)] == b't'
-      && full_str[si(iter_index + 6, full_str)] == b's'
-      && is_word_bounded(full_str, iter_index, iter_index + 7)
-}
-
-// check if preceded by var, let, const
-pub fn is_var_declaration(full_str: &[u8], identifier_start_index: usize) -> bool {
-  let prev_non_whitespace_index =
-      get_nearest_non_whitespace_index_left(full_str, identifier_start_index);
-
-  // var
-  if full_str[prev_non_whitespace_index] == b'r'
-      && full_str[prev_non_whitespace_index.saturating_sub(1)] == b'a'
-      && full_str[prev_non_whitespace_index.saturating_sub(2)] == b'v'
-      && is_word_bounded(
-          full_str,
-          prev_non_whitespace_index - 2,
-          prev_non_whitespace_index + 1,
-      )
-  {
-      return true;
-  }
-
-  // let
-  if full_str[prev_non_whitespace_index] == b't'
-      && full_str[prev_non_whitespace_index.saturating_sub(1)] == b'e'
-      && full_str[prev_non_whitespace_index.saturating_sub(2)] == b'l'
-      && is_word_bounded(
-          full_str,
-          prev_non_whitespace_index - 2,
-          prev_non_whitespace_index + 1,
-      )
-  {
-      return true;
-  }
-
-  // const
-  if full_str[prev_non_whitespace_index] == b't'
-      && full_str[prev_non_whitespace_index.saturating_sub(1)] == b's'
-      && full_str[prev_non_whitespace_index.saturating_sub(2)] == b'n'
-      && full_str[prev_non_whitespace_index.saturating_sub(3)] == b'o'
-      && full_str[prev_non_whitespace_index.saturating_sub(4)] == b'c'
-      && is_word_bounded(
-          full_str,
-          prev_non_whitespace_index - 4,
-          prev_non_whitespace_index + 1,
-      )
-  {
-      return true;
-  }
-
-  false
-}
-
-// check if it's a function parameter that's creating a scope
-pub fn is_function_param_declaration(
-  full_str: &[u8],
-  identifier_start_index: usize,
-  identifier_end_index: usize,
-) -> bool {
-  let prev_non_whitespace_index =
-      get_nearest_non_whitespace_index_left(full_str, identifier_start_index);
-  let next_non_whitespace_index =
-      get_nearest_non_whitespace_index_right(full_str, identifier_end_index);
-
-  // function(identifier) {}
-  // function(foo, identifier) {}
-  // function(foo, identifier, bar) {}
-  // function(foo = identifier) {}
-  if full_str[prev_non_whitespace_index] == b'=' {
-      return false;
-  }
-  if full_str[prev_non_whitespace_index] == b'(' || full_str[prev_non_whitespace_index] == b',' {
-      return true;
-  }
-  if full_str[next_non_whitespace_index] == b')' || full_str[next_non_whitespace_index] == b',' {
-      return true;
-  }
-
-  // identifier => {}
-  if full_str[next_non_whitespace_index] == b'='
-      && full_str[next_non_whitespace_index + 1] == b'>'
-  {
-      return true;
-  }
-
-  false
+  is_word_at(full_str, iter_index, b"exports")
 }
 
 // whether the identifier is preceded by a regex-possible keyword (in reverse check)
 // if, else, return, while, yield
 pub fn is_slash_preceded_by_regex_possible_keyword(full_str: &[u8], char_index: usize) -> bool {
+  if char_index >= full_str.len() {
+      return false;
+  }
   if full_str[char_index] == b'f' && full_str[char_index.saturating_sub(1)] == b'i' {
       return true;
   }
@@ -246,9 +143,110 @@ pub fn is_slash_preceded_by_regex_possible_keyword(full_str: &[u8], char_index:
       && full_str[char_index.saturating_sub(1)] == b'l'
       && full_str[char_index.saturating_sub(2)] == b'e'
       && full_str[char_index.saturating_sub(3)] == b'i'
-      && full_str[char_index.saturating_sub(3)] == b'y'
+      && full_str[char_index.saturating_sub(4)] == b'y'
   {
       return true;
   }
   false
 }
+
+// the trimmed line containing the byte index, truncated to a readable length
+pub fn get_line_snippet(s: &str, char_index: usize) -> String {
+  let b = s.as_bytes();
+  let char_index = char_index.min(b.len());
+  let line_start = b[..char_index].iter().rposition(|&v| v == b'\n').map_or(0, |pos| pos + 1);
+  let line_end = b[char_index..]
+      .iter()
+      .position(|&v| v == b'\n' || v == b'\r')
+      .map_or(b.len(), |pos| char_index + pos);
+  let line = s[line_start..line_end].trim();
+  match line.char_indices().nth(80) {
+      Some((pos, _)) => format!("{}...", &line[..pos]),
+      None => line.to_string(),
+  }
+}
+
+// decode the escapes of a js string literal's content (without the quotes)
+pub fn unescape_js_string(raw: &str) -> String {
+  if !raw.contains('\\') {
+      return raw.to_string();
+  }
+  let mut result = String::with_capacity(raw.len());
+  let mut chars = raw.chars().peekable();
+  while let Some(c) = chars.next() {
+      if c != '\\' {
+          result.push(c);
+          continue;
+      }
+      let escaped = match chars.next() {
+          Some(escaped) => escaped,
+          None => break,
+      };
+      match escaped {
+          'n' => result.push('\n'),
+          'r' => result.push('\r'),
+          't' => result.push('\t'),
+          'b' => result.push('\u{8}'),
+          'f' => result.push('\u{c}'),
+          'v' => result.push('\u{b}'),
+          '0' => result.push('\0'),
+          // line continuation
+          '\n' | '\u{2028}' | '\u{2029}' => {}
+          '\r' => {
+              if chars.peek() == Some(&'\n') {
+                  chars.next();
+              }
+          }
+          'x' => {
+              let hex: String = chars.by_ref().take(2).collect();
+              push_code_point(&mut result, &hex);
+          }
+          'u' => {
+              let code_point = read_unicode_escape(&mut chars);
+              // a surrogate pair is written as two escapes, e.g. `\uD83D\uDE00`
+              let code_point = match code_point {
+                  Some(high @ 0xD800..=0xDBFF) => {
+                      let mut next = chars.clone();
+                      let low = match (next.next(), next.next()) {
+                          (Some('\\'), Some('u')) => read_unicode_escape(&mut next),
+                          _ => None,
+                      };
+                      match low {
+                          Some(low @ 0xDC00..=0xDFFF) => {
+                              chars = next;
+                              Some(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                          }
+                          _ => code_point,
+                      }
+                  }
+                  _ => code_point,
+              };
+              result.push(
+                  code_point
+                      .and_then(char::from_u32)
+                      .unwrap_or(char::REPLACEMENT_CHARACTER),
+              );
+          }
+          _ => result.push(escaped),
+      }
+  }
+  result
+}
+
+// the code point of a `\u` escape, after the `u`, e.g. `00e9` or `{1F600}`
+fn read_unicode_escape(chars: &mut Peekable<Chars>) -> Option<u32> {
+  let hex: String = if chars.peek() == Some(&'{') {
+      chars.next();
+      chars.by_ref().take_while(|&v| v != '}').collect()
+  } else {
+      chars.by_ref().take(4).collect()
+  };
+  u32::from_str_radix(&hex, 16).ok()
+}
+
+fn push_code_point(result: &mut String, hex: &str) {
+  match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+      Some(c) => result.push(c),
+      None => result.push(char::REPLACEMENT_CHARACTER),
+  }
+}
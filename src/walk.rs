@@ -1,7 +1,16 @@
+use crate::diagnostic::JsDiagnosticKind;
 use crate::utils::{
-    get_nearest_non_whitespace_index_left, is_backslash_escaped,
-    is_slash_preceded_by_regex_possible_keyword,
+    get_identifier_end, get_nearest_non_whitespace_index_left, is_backslash_escaped,
+    is_slash_preceded_by_regex_possible_keyword, is_word_at, is_word_ending_at,
+    skip_whitespace_right,
 };
+use crate::JsDialect;
+
+// U+FEFF encoded as utf-8
+pub const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// the most bytes after an item that can change what it is, e.g. `<!--` or `extends` in tsx
+const LOOKAHEAD: usize = 16;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WalkCallbackResult {
@@ -9,80 +18,436 @@ pub enum WalkCallbackResult {
     Break,
 }
 
-pub fn walk<F>(s: &str, mut cb: F)
-where
-    F: FnMut(&[u8], &mut usize, u8) -> WalkCallbackResult,
-{
-    let mut i = 0;
-    let b = s.as_bytes();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkItemKind {
+    // a single byte of js code, anything that isn't skipped below
+    Code,
+    // a utf-8 byte order mark at the start of the file, which is ignored like whitespace
+    Bom,
+    // `#!/usr/bin/env node`, only at the very start of the file, after the byte order mark
+    Hashbang,
+    LineComment,
+    BlockComment,
+    // html-like comments, `<!--` or `-->` at the start of a line, until the end of the line
+    HtmlComment,
+    String,
+    // a template literal chunk, e.g. `foo${, }bar${ or }baz`
+    Template,
+    Regex,
+    // jsx markup that isn't js, e.g. tags, attributes and text
+    Jsx,
+}
 
-    // parsing state variables
-    //
-    // template literals can contain js via ${}, when this happens, we increase the depth by 1
-    // for each js within. When the depth is more than 0, we need to do special checks to
+// jsx only. where the walker is within jsx, from the outermost to the innermost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsxContext {
+    // inside a tag, e.g. `<div foo="bar"` or `</div`
+    Tag { closing: bool },
+    // element children, e.g. `<div>text</div>`
+    Children,
+    // js in a {} expression container, with the number of unclosed { within
+    Expression(usize),
+    // js in a template literal ${}, with the number of unclosed { within
+    Template(usize),
+}
+
+// a range of bytes the walker has visited. `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkItem {
+    pub kind: WalkItemKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+// step-by-step walker that classifies ranges of the source. every item is yielded once and the
+// walker moves past it. consumers can set `i` to skip ahead.
+#[derive(Clone)]
+pub struct Walker<'a> {
+    b: &'a [u8],
+    pub i: usize,
+    // template literals can contain js via ${}, when this happens, we push the offset of the
+    // opening ` for each js within. When the stack isn't empty, we need to do special checks to
     // know when we reach the end of the js.
     //
     // const foo = `hello ${world}`
     // |----------||------||-----||
     //       0                1
-    let mut template_literal_js_depth = 0;
+    template_literal_starts: Vec<usize>,
+    dialect: JsDialect,
+    // typescript only. generics like `a<b>(c) / d` make the division / regex heuristic below
+    // unreliable, so we track whether each open paren belongs to `if`, `while`, `for` or `with`,
+    // which is the only time a `/` after `)` starts a regex
+    paren_stack: Vec<bool>,
+    last_closed_paren_is_keyword: bool,
+    // jsx only. since jsx and js can be nested in each other, braces need to be tracked to know
+    // when a {} expression container or template literal ${} ends
+    jsx_stack: Vec<JsxContext>,
+    // unterminated constructs found so far, with their byte ranges
+    pub diagnostics: Vec<(JsDiagnosticKind, usize, usize)>,
+}
 
-    while i < b.len() {
-        let c = b[i];
+impl<'a> Walker<'a> {
+    pub fn with_dialect(s: &'a str, dialect: JsDialect) -> Self {
+        Walker {
+            b: s.as_bytes(),
+            i: 0,
+            template_literal_starts: Vec::new(),
+            dialect,
+            paren_stack: Vec::new(),
+            last_closed_paren_is_keyword: false,
+            jsx_stack: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
 
-        // single line comment, ignore until \n
-        if c == b'/' && b[i + 1] == b'/' {
-            let new_line_pos = match b[i + 2..].iter().position(|&v| v == b'\n' || v == b'\r') {
-                Some(pos) => {
-                    if pos > 0 && b[i + 2 + pos + 1] == b'\n' {
-                        pos + 1
-                    } else {
-                        pos
+    // the walker without its input, to keep it while more input is read. see `resume`
+    pub fn suspend(self) -> Walker<'static> {
+        Walker {
+            b: &[],
+            i: self.i,
+            template_literal_starts: self.template_literal_starts,
+            dialect: self.dialect,
+            paren_stack: self.paren_stack,
+            last_closed_paren_is_keyword: self.last_closed_paren_is_keyword,
+            jsx_stack: self.jsx_stack,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    // same as `next`, for input that's still being read. returns `None` without moving when the
+    // item reaches the end of the input, so more input could change it, e.g. a string that
+    // isn't closed yet or a `/` that could start a comment
+    pub fn next_complete(&mut self) -> Option<WalkItem> {
+        let diagnostics = std::mem::take(&mut self.diagnostics);
+        let snapshot = self.clone();
+        self.diagnostics = diagnostics;
+        let diagnostics_len = self.diagnostics.len();
+
+        match self.next() {
+            Some(item) if !self.is_open_ended(item) => Some(item),
+            _ => {
+                let diagnostics = std::mem::take(&mut self.diagnostics);
+                *self = snapshot;
+                self.diagnostics = diagnostics;
+                self.diagnostics.truncate(diagnostics_len);
+                None
+            }
+        }
+    }
+
+    // whether more bytes after the item could change it
+    fn is_open_ended(&self, item: WalkItem) -> bool {
+        if item.end + LOOKAHEAD <= self.b.len() {
+            return false;
+        }
+        // `<!--`, `-->`, jsx, `//` and `#!` look ahead
+        if matches!(self.b[item.start], b'<' | b'-' | b'/' | b'#') {
+            return true;
+        }
+        // unterminated items go until the end, code is a single byte
+        item.end == self.b.len() && item.kind != WalkItemKind::Code
+    }
+
+    // whether the walker is in plain js, not within a template literal ${} or jsx
+    pub fn is_at_rest(&self) -> bool {
+        self.template_literal_starts.is_empty() && self.jsx_stack.is_empty()
+    }
+
+    fn track_paren(&mut self, c: u8) {
+        if c == b'(' {
+            let left = get_nearest_non_whitespace_index_left(self.b, self.i);
+            let is_keyword = [&b"if"[..], b"while", b"for", b"with"]
+                .iter()
+                .any(|word| is_word_ending_at(self.b, left, word));
+            self.paren_stack.push(is_keyword);
+        } else if c == b')' {
+            self.last_closed_paren_is_keyword = self.paren_stack.pop().unwrap_or(false);
+        }
+    }
+
+    // typescript only, whether a / after ) or ] is a division
+    fn is_division_after_bracket(&self, left: usize) -> bool {
+        if !self.dialect.is_ts() {
+            return false;
+        }
+        match self.b[left] {
+            b')' => !self.last_closed_paren_is_keyword,
+            b']' => true,
+            _ => false,
+        }
+    }
+
+    // whether the < at `i` starts a jsx element instead of being a comparison
+    fn is_jsx_start(&self, i: usize) -> bool {
+        let b = self.b;
+        match b.get(i + 1) {
+            // fragment
+            Some(b'>') => {}
+            Some(&v) if v.is_ascii_alphabetic() || v == b'_' || v == b'$' => {
+                // typescript generics in tsx, <T,>() => {} or <T extends unknown>() => {}
+                if self.dialect.is_ts() {
+                    let name_end = get_identifier_end(b, i + 1);
+                    let next = skip_whitespace_right(b, name_end);
+                    if b.get(next) == Some(&b',') || is_word_at(b, next, b"extends") {
+                        return false;
                     }
                 }
-                None => break, // assume reach end of file
-            };
-            i += 2 + new_line_pos + 1;
-            continue;
+            }
+            _ => return false,
         }
 
-        // multi line comment, ignore until */
-        if c == b'/' && b[i + 1] == b'*' {
-            let closing_pos = match b[i + 3..]
+        // jsx can only be where an expression starts, e.g. not `a < b`
+        if b[..i].iter().all(|v| v.is_ascii_whitespace()) {
+            return true;
+        }
+        let left = get_nearest_non_whitespace_index_left(b, i);
+        if b[left].is_ascii_alphanumeric() || b[left] == b'_' || b[left] == b'$' {
+            return [
+                &b"return"[..],
+                b"yield",
+                b"default",
+                b"case",
+                b"in",
+                b"of",
+                b"typeof",
+                b"void",
+                b"await",
+                b"do",
+                b"else",
+            ]
+            .iter()
+            .any(|word| is_word_ending_at(b, left, word));
+        }
+        !matches!(b[left], b')' | b']' | b'}' | b'.' | b'"' | b'\'' | b'`')
+    }
+
+    // walk jsx markup in a tag or element children
+    fn next_jsx(&mut self, context: JsxContext) -> Option<WalkItem> {
+        let b = self.b;
+        let i = self.i;
+        let c = b[i];
+
+        if c == b'{' {
+            self.jsx_stack.push(JsxContext::Expression(0));
+            return self.item(WalkItemKind::Jsx, i + 1);
+        }
+
+        if context == JsxContext::Children {
+            if c == b'<' {
+                let next = skip_whitespace_right(b, i + 1);
+                if b.get(next) == Some(&b'/') {
+                    // closing tag, the children end here
+                    self.jsx_stack.pop();
+                    self.jsx_stack.push(JsxContext::Tag { closing: true });
+                    return self.item(WalkItemKind::Jsx, next + 1);
+                }
+                self.jsx_stack.push(JsxContext::Tag { closing: false });
+                return self.item(WalkItemKind::Jsx, i + 1);
+            }
+            // text
+            let end = b[i..]
                 .iter()
-                .enumerate()
-                .position(|(j, &v)| v == b'/' && b[i + 3 + j - 1] == b'*')
+                .position(|&v| v == b'{' || v == b'<')
+                .map_or(b.len(), |pos| i + pos);
+            return self.item(WalkItemKind::Jsx, end);
+        }
+
+        let closing = context == (JsxContext::Tag { closing: true });
+        match c {
+            b'>' => {
+                self.jsx_stack.pop();
+                if !closing {
+                    self.jsx_stack.push(JsxContext::Children);
+                }
+                self.item(WalkItemKind::Jsx, i + 1)
+            }
+            b'/' if b.get(i + 1) == Some(&b'>') => {
+                // self-closing
+                self.jsx_stack.pop();
+                self.item(WalkItemKind::Jsx, i + 2)
+            }
+            // attribute strings don't have escapes
+            b'"' | b'\'' => match b[i + 1..].iter().position(|&v| v == c) {
+                Some(pos) => self.item(WalkItemKind::Jsx, i + 1 + pos + 1),
+                None => self.unterminated(
+                    WalkItemKind::Jsx,
+                    JsDiagnosticKind::UnterminatedString,
+                    b.len(),
+                ),
+            },
+            // tag names, attribute names, whitespace, =
+            _ => {
+                let end = b[i + 1..]
+                    .iter()
+                    .position(|&v| matches!(v, b'{' | b'>' | b'/' | b'"' | b'\''))
+                    .map_or(b.len(), |pos| i + 1 + pos);
+                self.item(WalkItemKind::Jsx, end)
+            }
+        }
+    }
+
+    // jsx only, handle { and } in js to know when an expression container or template literal
+    // ends. returns the item if the brace is jsx markup or a template literal chunk
+    fn next_jsx_brace(&mut self, c: u8) -> Option<WalkItem> {
+        match (c, self.jsx_stack.last_mut()) {
+            (b'{', Some(JsxContext::Expression(depth) | JsxContext::Template(depth))) => {
+                *depth += 1;
+            }
+            (b'}', Some(JsxContext::Expression(0))) => {
+                self.jsx_stack.pop();
+                return self.item(WalkItemKind::Jsx, self.i + 1);
+            }
+            (b'}', Some(JsxContext::Expression(depth) | JsxContext::Template(depth)))
+                if *depth > 0 =>
             {
-                Some(pos) => pos,
-                None => break, // assume reach end of file
+                *depth -= 1;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn item(&mut self, kind: WalkItemKind, end: usize) -> Option<WalkItem> {
+        let item = WalkItem {
+            kind,
+            start: self.i,
+            end,
+        };
+        self.i = end;
+        Some(item)
+    }
+
+    // the construct isn't closed, report it and yield it until `end` so walking can continue
+    fn unterminated(
+        &mut self,
+        kind: WalkItemKind,
+        diagnostic: JsDiagnosticKind,
+        end: usize,
+    ) -> Option<WalkItem> {
+        self.diagnostics.push((diagnostic, self.i, end));
+        self.item(kind, end)
+    }
+}
+
+impl Walker<'static> {
+    // continue a suspended walker on its input, which can have more bytes at the end now, or
+    // fewer at the start if `i` is moved back by as many
+    pub fn resume(self, s: &str) -> Walker<'_> {
+        let mut walker: Walker<'_> = self;
+        walker.b = s.as_bytes();
+        walker
+    }
+}
+
+impl<'a> Iterator for Walker<'a> {
+    type Item = WalkItem;
+
+    fn next(&mut self) -> Option<WalkItem> {
+        let b = self.b;
+        let i = self.i;
+
+        if i >= b.len() {
+            // a template literal whose ${} is still open at the end
+            if let Some(&start) = self.template_literal_starts.first() {
+                self.diagnostics
+                    .push((JsDiagnosticKind::UnterminatedTemplate, start, b.len()));
+                self.template_literal_starts.clear();
+            }
+            return None;
+        }
+
+        let c = b[i];
+
+        if self.dialect.is_jsx() {
+            if let Some(&context @ (JsxContext::Tag { .. } | JsxContext::Children)) =
+                self.jsx_stack.last()
+            {
+                return self.next_jsx(context);
+            }
+            if c == b'{' || c == b'}' {
+                if let Some(item) = self.next_jsx_brace(c) {
+                    return Some(item);
+                }
+            }
+            if c == b'<' && self.is_jsx_start(i) {
+                self.jsx_stack.push(JsxContext::Tag { closing: false });
+                return self.item(WalkItemKind::Jsx, i + 1);
+            }
+        }
+
+        if i == 0 && b.starts_with(BOM) {
+            return self.item(WalkItemKind::Bom, BOM.len());
+        }
+
+        // hashbang, ignore until \n
+        if (i == 0 || (i == BOM.len() && b.starts_with(BOM))) && b[i..].starts_with(b"#!") {
+            return self.item(WalkItemKind::Hashbang, line_end(b, i + 2));
+        }
+
+        // single line comment, ignore until \n
+        if c == b'/' && b.get(i + 1) == Some(&b'/') {
+            return self.item(WalkItemKind::LineComment, line_end(b, i + 2));
+        }
+
+        // html-like comments from annex b, ignore until \n. they're only allowed in scripts, but
+        // `<!--` and `-->` at the start of a line are unlikely to be code in modules either
+        if (c == b'<' && b[i..].starts_with(b"<!--"))
+            || (c == b'-' && b[i..].starts_with(b"-->") && is_line_start(b, i))
+        {
+            return self.item(WalkItemKind::HtmlComment, line_end(b, i + 3));
+        }
+
+        // multi line comment, ignore until */
+        if c == b'/' && b.get(i + 1) == Some(&b'*') {
+            return match b[i + 2..].windows(2).position(|v| v == b"*/") {
+                Some(pos) => self.item(WalkItemKind::BlockComment, i + 2 + pos + 2),
+                None => self.unterminated(
+                    WalkItemKind::BlockComment,
+                    JsDiagnosticKind::UnterminatedComment,
+                    b.len(),
+                ),
             };
-            i += 3 + closing_pos + 1;
-            continue;
         }
 
-        // single and double quotes, ignore until quote end
+        // single and double quotes, ignore until quote end. strings can't span lines, so an
+        // unterminated string ends at the line end
         if c == b'\'' || c == b'"' {
-            let closing_pos = match b[i + 1..].iter().enumerate().position(|(j, &v)| {
-                if v == c {
-                    return !is_backslash_escaped(b, i + 1 + j);
-                } else {
-                    return false;
+            return match b[i + 1..].iter().enumerate().position(|(j, &v)| {
+                let k = i + 1 + j;
+                match v {
+                    // `\` followed by \r\n is a single line continuation
+                    b'\n' if b[k - 1] == b'\r' && is_backslash_escaped(b, k - 1) => false,
+                    b'\n' | b'\r' => !is_backslash_escaped(b, k),
+                    _ => v == c && !is_backslash_escaped(b, k),
                 }
             }) {
-                Some(pos) => pos,
-                None => break, // assume reach end of file
+                Some(pos) if b[i + 1 + pos] == c => {
+                    self.item(WalkItemKind::String, i + 1 + pos + 1)
+                }
+                Some(pos) => self.unterminated(
+                    WalkItemKind::String,
+                    JsDiagnosticKind::UnterminatedString,
+                    i + 1 + pos,
+                ),
+                None => self.unterminated(
+                    WalkItemKind::String,
+                    JsDiagnosticKind::UnterminatedString,
+                    b.len(),
+                ),
             };
-            // println!("quotes {}", &s[i..i + 1 + closing_pos + 1]);
-            i += 1 + closing_pos + 1;
-            continue;
         }
 
         // template literal, skip until ` or ${
         // template literal, but is inner js code, also check for closing }
-        if c == b'`' || (template_literal_js_depth > 0 && c == b'}') {
+        let is_template_continuation = if self.dialect.is_jsx() {
+            c == b'}' && self.jsx_stack.last() == Some(&JsxContext::Template(0))
+        } else {
+            !self.template_literal_starts.is_empty() && c == b'}'
+        };
+        if c == b'`' || is_template_continuation {
             let closing_pos = match b[i + 1..].iter().enumerate().position(|(j, &v)| {
                 // capture ${
-                if v == b'$' && b[i + 1 + j + 1] == b'{' {
+                if v == b'$' && b.get(i + 1 + j + 1) == Some(&b'{') {
                     return !is_backslash_escaped(b, i + 1 + j);
                 }
                 // capture `
@@ -92,84 +457,152 @@ where
                 false
             }) {
                 Some(pos) => pos,
-                None => break, // assume reach end of file
+                None => {
+                    // the rest of the template is reported instead of its start
+                    if c == b'}' {
+                        self.template_literal_starts.pop();
+                    }
+                    return self.unterminated(
+                        WalkItemKind::Template,
+                        JsDiagnosticKind::UnterminatedTemplate,
+                        b.len(),
+                    );
+                }
             };
             if b[i + 1 + closing_pos] == b'$' {
                 // only increment for `, since for ${ it's already incremented
                 if c == b'`' {
-                    template_literal_js_depth += 1;
+                    self.template_literal_starts.push(i);
+                    if self.dialect.is_jsx() {
+                        self.jsx_stack.push(JsxContext::Template(0));
+                    }
                 }
-                // println!("temlitopen {}", &s[i..i + 1 + closing_pos + 2]);
-                i += 1 + closing_pos + 2;
+                return self.item(WalkItemKind::Template, i + 1 + closing_pos + 2);
             } else {
                 // only decrement for }, since for ` it's already decremented
                 if c == b'}' {
-                    template_literal_js_depth -= 1;
+                    self.template_literal_starts.pop();
+                    if self.dialect.is_jsx() {
+                        self.jsx_stack.pop();
+                    }
                 }
-                // println!("temlitclose {}", &s[i..i + 1 + closing_pos + 1]);
-                i += 1 + closing_pos + 1;
+                return self.item(WalkItemKind::Template, i + 1 + closing_pos + 1);
             }
-            continue;
         };
 
         // skip regex
         if c == b'/' {
-            let left = get_nearest_non_whitespace_index_left(&b, i);
+            let left = get_nearest_non_whitespace_index_left(b, i);
             // knowing when a / is a division or the start of a regex ia PAIN. luckily this below
             // works good enough, by checking the we're not preceding any variables, but if is,
             // only allow specific keywords (see function for specific keywords)
             // Thanks for inspiration: https://github.com/guybedford/es-module-lexer/blob/559a550318fcdfe20c60cb322c147905b5aadf9f/src/lexer.c#L186-L200
-            if !b[left].is_ascii_alphanumeric()
-                || is_slash_preceded_by_regex_possible_keyword(&b, left)
+            if (!b[left].is_ascii_alphanumeric()
+                || is_slash_preceded_by_regex_possible_keyword(b, left))
+                && !self.is_division_after_bracket(left)
             {
+                // `(a) / b / c` or `if (a) /b/.test(c)`, only typescript tracks parens to know
+                let is_guess = i > 0
+                    && match b[left] {
+                        b')' | b']' => !self.dialect.is_ts(),
+                        b'}' => true,
+                        _ => false,
+                    };
                 // mini [] state, anything in [] is literal, so skip / detection
                 let mut is_in_bracket = false;
                 let re_closing_pos = match b[i + 1..].iter().enumerate().position(|(j, &v)| {
                     if v == b'[' && !is_backslash_escaped(b, i + 1 + j) {
                         is_in_bracket = true;
-                        return false;
+                        false
                     } else if v == b']' && !is_backslash_escaped(b, i + 1 + j) {
                         is_in_bracket = false;
-                        return false;
+                        false
                     } else if v == b'\n' {
                         // TODO: this might be redundant now that i implemented the divisiion / regex heuristic
-                        return true;
-                    } else if !is_in_bracket && v == b'/' && !is_backslash_escaped(b, i + 1 + j) {
-                        return true;
+                        true
+                    } else {
+                        !is_in_bracket && v == b'/' && !is_backslash_escaped(b, i + 1 + j)
                     }
-                    false
                 }) {
                     Some(pos) => pos,
-                    None => break, // assume reach end of file
+                    None => {
+                        return self.unterminated(
+                            WalkItemKind::Regex,
+                            JsDiagnosticKind::UnterminatedRegex,
+                            b.len(),
+                        )
+                    }
                 };
-                if b[i + 1 + re_closing_pos] == b'\n' {
-                    // it's a division, not a regex
-                    i += 1;
-                    continue;
-                } else {
+                if b[i + 1 + re_closing_pos] != b'\n' {
                     // we also need to skip regex modifiers
-                    let re_modifier_pos = match b[i + 1 + re_closing_pos + 1..]
-                        .iter()
-                        .position(|&v| !v.is_ascii_alphabetic())
-                    {
-                        Some(pos) => pos,
-                        None => break, // assume reach end of file
-                    };
-                    // println!(
-                    //     "regex {}",
-                    //     &s[i..i + 1 + re_closing_pos + 1 + re_modifier_pos]
-                    // );
-                    i += 1 + re_closing_pos + 1 + re_modifier_pos;
-                    continue;
+                    let end = scan_regex_flags(b, i + 1 + re_closing_pos + 1);
+                    if is_guess {
+                        self.diagnostics
+                            .push((JsDiagnosticKind::AmbiguousSlash, i, end));
+                    }
+                    return self.item(WalkItemKind::Regex, end);
                 }
+                // it's a division, not a regex
+                self.diagnostics
+                    .push((JsDiagnosticKind::AmbiguousSlash, i, i + 1));
             }
         }
 
+        if self.dialect.is_ts() {
+            self.track_paren(c);
+        }
+
+        self.item(WalkItemKind::Code, i + 1)
+    }
+}
+
+// the index of the \n or \r that ends the line, or the end of the file
+fn line_end(b: &[u8], start: usize) -> usize {
+    b[start.min(b.len())..]
+        .iter()
+        .position(|&v| v == b'\n' || v == b'\r')
+        .map_or(b.len(), |pos| start + pos)
+}
+
+// whether only spaces and tabs are before `i` on its line
+fn is_line_start(b: &[u8], i: usize) -> bool {
+    b[..i]
+        .iter()
+        .rev()
+        .find(|&&v| v != b' ' && v != b'\t')
+        .is_none_or(|&v| v == b'\n' || v == b'\r')
+}
+
+fn scan_regex_flags(b: &[u8], start: usize) -> usize {
+    b[start..]
+        .iter()
+        .position(|&v| !v.is_ascii_alphabetic())
+        .map_or(b.len(), |pos| start + pos)
+}
+
+// walk the js code bytes only, skipping comments, strings, template literals and regexes.
+// the callback can move `i` to skip ahead, it's incremented by 1 after every `Continue`.
+// returns the diagnostics of the walked part.
+pub fn walk<F>(s: &str, dialect: JsDialect, mut cb: F) -> Vec<(JsDiagnosticKind, usize, usize)>
+where
+    F: FnMut(&[u8], &mut usize, u8) -> WalkCallbackResult,
+{
+    let b = s.as_bytes();
+    let mut walker = Walker::with_dialect(s, dialect);
+
+    while let Some(item) = walker.next() {
+        if item.kind != WalkItemKind::Code {
+            continue;
+        }
+
+        let mut i = item.start;
+        let c = b[i];
         let result = cb(b, &mut i, c);
         if result == WalkCallbackResult::Break {
             break;
-        } else if result == WalkCallbackResult::Continue {
-            i += 1;
         }
+        walker.i = i + 1;
     }
+
+    walker.diagnostics
 }
@@ -0,0 +1,46 @@
+use fmu::{
+    guess_js_syntax_bytes, hashbang_bytes, parse_directives, parse_esm_bytes, parse_requires_bytes,
+    resolve_package_bytes, JsDialect, JsResolveError, JsSyntax,
+};
+
+#[test]
+fn bytes() {
+    let b = b"import a from 'a'\nexport default a";
+    assert_eq!(guess_js_syntax_bytes(b, JsDialect::JS), JsSyntax::ESM);
+    let imports = parse_esm_bytes(b, JsDialect::JS).imports;
+    assert_eq!(imports[0].specifier.as_deref(), Some("a"));
+    assert_eq!(imports[0].specifier_span.as_ref().unwrap().start, 15);
+
+    let resolution = resolve_package_bytes(br#"{ "exports": "./a.js" }"#, "pkg", vec![]);
+    assert_eq!(resolution.target.as_deref(), Some("./a.js"));
+    let resolution = resolve_package_bytes(b"{ \"main\": \"\xff\" }", "pkg", vec![]);
+    assert_eq!(resolution.target.as_deref(), Some("./\u{FFFD}"));
+    let resolution = resolve_package_bytes(b"\xff", "pkg", vec![]);
+    assert_eq!(resolution.error, Some(JsResolveError::InvalidPackageConfig));
+}
+
+#[test]
+fn bytes_invalid_utf8() {
+    let b = b"const a = '\xff\xfe'\nrequire('b')";
+    assert_eq!(guess_js_syntax_bytes(b, JsDialect::JS), JsSyntax::CJS);
+    assert_eq!(
+        guess_js_syntax_bytes(b"\xff", JsDialect::JS),
+        JsSyntax::Unknown
+    );
+
+    // the offsets are into the decoded string, where each invalid byte is 3 bytes long
+    let span = parse_requires_bytes(b"\xff\xff require('x')", JsDialect::JS)[0].span;
+    assert_eq!((span.start, span.utf16_start), (7, 3));
+    assert_eq!(span.end - span.start, "require('x')".len());
+}
+
+#[test]
+fn bom() {
+    let b = b"\xEF\xBB\xBF#!/usr/bin/env node\n'use strict'\nrequire('a')";
+    assert_eq!(guess_js_syntax_bytes(b, JsDialect::JS), JsSyntax::CJS);
+    assert_eq!(hashbang_bytes(b), Some("/usr/bin/env node".to_string()));
+
+    let s = "\u{FEFF}'use client'\nexport default 1";
+    assert_eq!(parse_directives(s)[0].name, "use client");
+    assert_eq!(parse_directives(s)[0].span.utf16_start, 1);
+}
@@ -0,0 +1,99 @@
+use fmu::{parse_cjs, parse_requires, JsDialect};
+
+#[test]
+fn exports() {
+    let s = r#"Object.defineProperty(exports, '__esModule', { value: true })
+exports.a = 1
+exports['b'] = 2
+module.exports.c = 3
+module.exports["d"] = exports.e = 4
+Object.defineProperty(module.exports, 'f', { get() { return 5 } })
+if (exports.a === 1) {}
+foo.exports.g = 6
+"#;
+    assert_eq!(
+        parse_cjs(s, JsDialect::JS).exports,
+        vec!["__esModule", "a", "b", "c", "d", "e", "f"]
+    );
+}
+
+#[test]
+fn object_literal() {
+    let s = r#"module.exports = {
+  a,
+  b: foo(1, 2),
+  'c': 3,
+  d() {},
+  get e() { return 1 },
+  ...require('./f'),
+}"#;
+    let result = parse_cjs(s, JsDialect::JS);
+    assert_eq!(result.exports, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(result.reexports, vec!["./f"]);
+}
+
+#[test]
+fn reexports() {
+    let s = r#"module.exports = require('a')
+__exportStar(require("b"), exports)
+__export(require('c'))
+tslib_1.__exportStar(require("d"), exports)
+"#;
+    assert_eq!(
+        parse_cjs(s, JsDialect::JS).reexports,
+        vec!["a", "b", "c", "d"]
+    );
+
+    let s = r#"function __exportStar(m, exports) {}
+__exportStar(require("a"), exports)
+function foo(__export) {
+  __export(require('b'))
+}
+__export(require('c'))
+"#;
+    assert_eq!(parse_cjs(s, JsDialect::JS).reexports, vec!["c"]);
+}
+
+#[test]
+fn shadowed() {
+    let s = r#"function foo(exports) {
+  exports.a = 1
+}
+exports.b = 2
+"#;
+    assert_eq!(parse_cjs(s, JsDialect::JS).exports, vec!["b"]);
+}
+
+#[test]
+fn requires() {
+    let s = r#"const a = require('a')
+const b = require(`b`)
+const c = require(`c${d}`)
+const e = require(name)
+require()
+foo.require('f')
+if (typeof require !== 'undefined') {}
+function g(require) {
+  require('h')
+}
+"#;
+    let requires = parse_requires(s, JsDialect::JS);
+    let result: Vec<_> = requires
+        .iter()
+        .map(|v| (v.specifier.as_deref(), v.dynamic))
+        .collect();
+    assert_eq!(
+        result,
+        vec![
+            (Some("a"), false),
+            (Some("b"), false),
+            (None, true),
+            (None, true)
+        ]
+    );
+    let span = requires[0].span;
+    assert_eq!(&s[span.start..span.end], "require('a')");
+    let span = requires[1].specifier_span.unwrap();
+    assert_eq!(&s[span.start..span.end], "b");
+    assert_eq!((span.line, span.column), (2, 19));
+}
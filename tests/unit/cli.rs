@@ -0,0 +1,109 @@
+use std::process::Command;
+
+fn fmu(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_fmu"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn cli_table() {
+    let (code, stdout) = fmu(&[
+        "tests/unit/samples/cjs_require.js",
+        "tests/unit/samples/format_umd.js",
+    ]);
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout,
+        "\
+PATH                               SYNTAX  FORMAT  CONFIDENCE
+tests/unit/samples/cjs_require.js  CJS     CJS     Medium
+tests/unit/samples/format_umd.js   CJS     UMD     High
+"
+    );
+}
+
+#[test]
+fn cli_ndjson_glob() {
+    let (code, stdout) = fmu(&["--output", "ndjson", "tests/unit/samples/esm_*_export.js"]);
+    assert_eq!(code, 0);
+    // overlapping paths list each file once
+    let (_, overlapping) = fmu(&[
+        "--output",
+        "ndjson",
+        "./tests/**/esm_*_export.js",
+        "tests/unit/samples/esm_default_export.js",
+        "./tests/unit/samples/esm_named_export.js",
+    ]);
+    assert_eq!(overlapping, stdout);
+    assert_eq!(
+        stdout,
+        "\
+{\"path\":\"tests/unit/samples/esm_default_export.js\",\"syntax\":\"ESM\",\"format\":\"ESM\",\"confidence\":\"High\",\"ambiguous\":false}
+{\"path\":\"tests/unit/samples/esm_named_export.js\",\"syntax\":\"ESM\",\"format\":\"ESM\",\"confidence\":\"High\",\"ambiguous\":false}
+"
+    );
+}
+
+#[test]
+fn cli_deny() {
+    let (code, stdout) = fmu(&["--output=json", "--deny", "cjs", "tests/unit/samples"]);
+    assert_eq!(code, 1);
+    assert!(stdout.starts_with("[{\"path\":\"tests/unit/samples/cjs_create_require_scope.js\""));
+    assert!(stdout.contains("tests/unit/samples/ts_esm.ts"));
+
+    let (code, _) = fmu(&["--deny", "CJS", "tests/unit/samples/esm_import_meta.js"]);
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn cli_errors() {
+    assert_eq!(fmu(&["tests/unit/samples/missing.js"]).0, 2);
+    assert_eq!(fmu(&["--output", "xml", "tests/unit/samples"]).0, 2);
+    assert_eq!(fmu(&["--deny", "cjss", "tests/unit/samples"]).0, 2);
+    assert_eq!(fmu(&[]).0, 2);
+    assert_eq!(fmu(&["--help"]).0, 0);
+}
+
+#[test]
+fn cli_hazard() {
+    let root = std::env::temp_dir().join(format!("fmu-cli-hazard-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("package.json"),
+        r#"{ "exports": { "import": "./index.mjs", "require": "./index.cjs" } }"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("index.mjs"), "export default 1").unwrap();
+    std::fs::write(root.join("index.cjs"), "module.exports = 1").unwrap();
+    let dir = root.to_string_lossy().replace('\\', "/");
+
+    let (code, stdout) = fmu(&["hazard", "--output", "ndjson", &dir]);
+    assert_eq!(code, 1);
+    assert_eq!(
+        stdout,
+        format!(
+            "{{\"package\":\"{}\",\"subpath\":\".\",\"kind\":\"DualPackage\",\"import\":\"./index.mjs\",\"require\":\"./index.cjs\",\"format\":null,\"syntax\":null}}\n",
+            dir
+        )
+    );
+
+    std::fs::write(
+        root.join("index.mjs"),
+        "export { default } from './index.cjs'",
+    )
+    .unwrap();
+    let (code, stdout) = fmu(&["hazard", &dir]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "PACKAGE  SUBPATH  HAZARD  FILES\n");
+
+    assert_eq!(fmu(&["hazard", "--deny", "CJS", &dir]).0, 2);
+    assert_eq!(fmu(&["hazard", "tests/unit/samples"]).0, 2);
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,117 @@
+use fmu::{parse_comments, JsCommentAnnotationKind, JsCommentKind, JsDialect};
+
+// (kind, name, value) of every annotation in `s`
+fn annotations(s: &str) -> Vec<(JsCommentAnnotationKind, String, Option<String>)> {
+    parse_comments(s, JsDialect::JS)
+        .into_iter()
+        .flat_map(|c| c.annotations)
+        .map(|a| (a.kind, a.name, a.value))
+        .collect()
+}
+
+#[test]
+fn comments() {
+    let s = "#!/usr/bin/env node\n// a\nconst b = '/* c */' /* d */\n<!-- e\n/* f";
+    let comments: Vec<_> = parse_comments(s, JsDialect::JS)
+        .into_iter()
+        .map(|c| (c.kind, c.text, c.span.line))
+        .collect();
+    assert_eq!(
+        comments,
+        vec![
+            (JsCommentKind::Hashbang, "/usr/bin/env node".to_string(), 1),
+            (JsCommentKind::Line, " a".to_string(), 2),
+            (JsCommentKind::Block, " d ".to_string(), 3),
+            (JsCommentKind::Html, " e".to_string(), 4),
+            (JsCommentKind::Block, " f".to_string(), 5),
+        ]
+    );
+}
+
+#[test]
+fn comments_in_jsx_text() {
+    let comments = parse_comments("<a>// not a comment</a> /* b */", JsDialect::JSX);
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].text, " b ");
+}
+
+#[test]
+fn comments_after_non_ascii() {
+    let comments = parse_comments("const café = '😀' // hi\n/* ü */", JsDialect::JS);
+    let texts: Vec<_> = comments.iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(texts, vec![" hi", " ü "]);
+    assert_eq!(comments[0].span.column, 18);
+}
+
+#[test]
+fn annotations_pure() {
+    use JsCommentAnnotationKind::*;
+    assert_eq!(
+        annotations("/*#__PURE__*/ a(); /* @__NO_SIDE_EFFECTS__ */ function b() {}"),
+        vec![
+            (Pure, "#__PURE__".to_string(), None),
+            (NoSideEffects, "@__NO_SIDE_EFFECTS__".to_string(), None),
+        ]
+    );
+    assert_eq!(annotations("/* __PURE__ */"), vec![]);
+}
+
+#[test]
+fn annotations_bundler() {
+    use JsCommentAnnotationKind::*;
+    assert_eq!(
+        annotations(
+            "import(/* webpackChunkName: \"a, b\", webpackExports: [\"c\", \"d\"] */ './a')"
+        ),
+        vec![
+            (
+                Webpack,
+                "webpackChunkName".to_string(),
+                Some("\"a, b\"".to_string())
+            ),
+            (
+                Webpack,
+                "webpackExports".to_string(),
+                Some("[\"c\", \"d\"]".to_string())
+            ),
+        ]
+    );
+    assert_eq!(annotations("/* webpack: 1, mywebpackFoo: 2 */"), vec![]);
+    assert_eq!(
+        annotations("import(/* @vite-ignore */ url)"),
+        vec![(ViteIgnore, "@vite-ignore".to_string(), None)]
+    );
+}
+
+#[test]
+fn annotations_legal_and_source_map() {
+    use JsCommentAnnotationKind::*;
+    assert_eq!(
+        annotations("/*! a */\n/** @license MIT */\n//! b\n/* @preserve */"),
+        vec![
+            (Legal, "!".to_string(), None),
+            (Legal, "@license".to_string(), None),
+            (Legal, "!".to_string(), None),
+            (Legal, "@preserve".to_string(), None),
+        ]
+    );
+    assert_eq!(
+        annotations("a\n//# sourceMappingURL=a.js.map\n//@ sourceMappingURL=b.js.map "),
+        vec![
+            (
+                SourceMappingUrl,
+                "sourceMappingURL".to_string(),
+                Some("a.js.map".to_string())
+            ),
+            (
+                SourceMappingUrl,
+                "sourceMappingURL".to_string(),
+                Some("b.js.map".to_string())
+            ),
+        ]
+    );
+    assert_eq!(
+        annotations("const a = '//# sourceMappingURL=a.js.map'"),
+        vec![]
+    );
+}
@@ -0,0 +1,73 @@
+use fmu::{analyze_js_syntax, guess_js_syntax_with_confidence};
+use fmu::{JsConfidence, JsDiagnosticKind, JsDialect, JsSyntax};
+
+fn guess(s: &str) -> (JsSyntax, JsConfidence, bool) {
+    let guess = guess_js_syntax_with_confidence(s, JsDialect::JS);
+    (guess.syntax, guess.confidence, guess.ambiguous)
+}
+
+#[test]
+fn confidence() {
+    assert_eq!(
+        guess("import a from 'a'\nexport default a"),
+        (JsSyntax::ESM, JsConfidence::High, false)
+    );
+    assert_eq!(
+        guess("module.exports = require('a')"),
+        (JsSyntax::CJS, JsConfidence::High, false)
+    );
+    assert_eq!(
+        guess("const a = require('a')"),
+        (JsSyntax::CJS, JsConfidence::Medium, false)
+    );
+    assert_eq!(
+        guess("import a from 'a'\nmodule.exports = a"),
+        (JsSyntax::Mixed, JsConfidence::Medium, false)
+    );
+    assert_eq!(
+        guess("foo()"),
+        (JsSyntax::Unknown, JsConfidence::Low, false)
+    );
+}
+
+#[test]
+fn ambiguous_slash() {
+    // `/ 2; export default /` could be a regex
+    let s = "const a = (b) / 2; export default c / 3";
+    assert_eq!(guess(s), (JsSyntax::Unknown, JsConfidence::Low, true));
+    let kinds: Vec<_> = analyze_js_syntax(s)
+        .diagnostics
+        .iter()
+        .map(|d| d.kind)
+        .collect();
+    assert_eq!(kinds, vec![JsDiagnosticKind::AmbiguousSlash]);
+
+    // the regex reaches the line end, so it's a division after all
+    let s = "const a = (b) / 2\nexport default a";
+    assert_eq!(guess(s), (JsSyntax::ESM, JsConfidence::Low, true));
+
+    // typescript knows which paren it is
+    let guess =
+        guess_js_syntax_with_confidence("const a = (b) / 2\nexport default a", JsDialect::TS);
+    assert!(!guess.ambiguous);
+}
+
+#[test]
+fn unbalanced_brace() {
+    let spans = |s: &str| -> Vec<_> {
+        analyze_js_syntax(s)
+            .diagnostics
+            .iter()
+            .map(|d| (d.kind, d.span.start))
+            .collect()
+    };
+    assert_eq!(
+        spans("}\nexport {}"),
+        vec![(JsDiagnosticKind::UnbalancedBrace, 0)]
+    );
+    assert_eq!(
+        spans("function a() {\nexport {}"),
+        vec![(JsDiagnosticKind::UnbalancedBrace, 24)]
+    );
+    assert_eq!(guess("export {}\n{").1, JsConfidence::Low);
+}
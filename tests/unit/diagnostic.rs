@@ -0,0 +1,91 @@
+use fmu::{analyze_js_syntax, analyze_js_syntax_with_dialect, parse_cjs, parse_esm};
+use fmu::{guess_js_syntax_with_confidence, parse_requires, rewrite_specifiers};
+use fmu::{JsDiagnosticKind, JsDialect, Lexer};
+use std::fs;
+
+#[test]
+fn unterminated() {
+    let kinds = |s: &str| -> Vec<_> {
+        analyze_js_syntax(s)
+            .diagnostics
+            .iter()
+            .map(|d| (d.kind, d.span.start, d.span.end))
+            .collect()
+    };
+    assert_eq!(
+        kinds("a = 'b"),
+        vec![(JsDiagnosticKind::UnterminatedString, 4, 6)]
+    );
+    assert_eq!(
+        kinds("/* a"),
+        vec![(JsDiagnosticKind::UnterminatedComment, 0, 4)]
+    );
+    assert_eq!(
+        kinds("`a${b}"),
+        vec![(JsDiagnosticKind::UnterminatedTemplate, 5, 6)]
+    );
+    // the ${} is still open at the end
+    assert_eq!(
+        kinds("`${"),
+        vec![(JsDiagnosticKind::UnterminatedTemplate, 0, 3)]
+    );
+    assert_eq!(
+        kinds("a = `a${b + `c${d"),
+        vec![(JsDiagnosticKind::UnterminatedTemplate, 4, 17)]
+    );
+    assert!(guess_js_syntax_with_confidence("`${", JsDialect::JS).ambiguous);
+    assert_eq!(
+        kinds("x = /a["),
+        vec![(JsDiagnosticKind::UnterminatedRegex, 4, 7)]
+    );
+    assert_eq!(kinds("a = 'b\\'c'"), vec![]);
+    // line continuations
+    assert_eq!(kinds("a = 'b\\\nc'"), vec![]);
+    assert_eq!(kinds("a = 'b\\\r\nc'"), vec![]);
+    assert_eq!(
+        kinds("a = 'b\r\nc"),
+        vec![(JsDiagnosticKind::UnterminatedString, 4, 6)]
+    );
+}
+
+#[test]
+fn recovers_after_string() {
+    let analysis = analyze_js_syntax("const a = 'b\nexport default a");
+    assert_eq!(analysis.diagnostics.len(), 1);
+    assert_eq!(analysis.diagnostics[0].span.line, 1);
+    assert_eq!(
+        parse_esm("const a = 'b\nexport default a", JsDialect::JS)
+            .exports
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn truncated_input() {
+    let mut sources: Vec<String> = fs::read_dir("tests/unit/samples")
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    sources.extend(
+        [
+            "import", "import.", "export", "require", "module", "exports", "define", "System",
+            "`${", "'\\", "\"\\\n", "/", "x /", "a\\", "$", "<", "<a", "<a b='", "ü'",
+        ]
+        .map(String::from),
+    );
+
+    for s in &sources {
+        for (end, _) in s.char_indices().chain([(s.len(), ' ')]) {
+            let s = &s[..end];
+            for dialect in [JsDialect::JS, JsDialect::TS, JsDialect::JSX, JsDialect::TSX] {
+                analyze_js_syntax_with_dialect(s, dialect);
+                Lexer::with_dialect(s, dialect).for_each(drop);
+                parse_esm(s, dialect);
+                parse_cjs(s, dialect);
+                parse_requires(s, dialect);
+                rewrite_specifiers(s, dialect, |v| Some(v.to_string()));
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use fmu::{guess_js_syntax, parse_directives, parse_hashbang, JsSyntax};
+
+fn names(s: &str) -> Vec<String> {
+    parse_directives(s).into_iter().map(|d| d.name).collect()
+}
+
+#[test]
+fn directives() {
+    assert_eq!(names("'use strict';\nfoo()"), vec!["use strict"]);
+    assert_eq!(
+        names("\"use client\"\n'use strict'\nexport default 1"),
+        vec!["use client", "use strict"]
+    );
+    assert_eq!(names("'use server'"), vec!["use server"]);
+    assert_eq!(
+        names("'use asm'; 'use strict'"),
+        vec!["use asm", "use strict"]
+    );
+    assert_eq!(names("foo()\n'use strict'"), Vec::<String>::new());
+}
+
+#[test]
+fn directives_after_hashbang_and_comments() {
+    let s = "#!/usr/bin/env node\n// license\n/* a\n b */\n'use strict'";
+    let directives = parse_directives(s);
+    assert_eq!(directives.len(), 1);
+    assert_eq!(directives[0].name, "use strict");
+    assert_eq!((directives[0].span.line, directives[0].span.column), (5, 0));
+    assert_eq!(
+        &s[directives[0].span.start..directives[0].span.end],
+        "'use strict'"
+    );
+}
+
+#[test]
+fn directives_not_expressions() {
+    assert_eq!(names("'use strict'.length"), Vec::<String>::new());
+    assert_eq!(names("'use strict'\n.length"), Vec::<String>::new());
+    assert_eq!(names("'use strict' + a"), Vec::<String>::new());
+    assert_eq!(names("'a'\n'b' + c"), vec!["a"]);
+    // escapes aren't decoded, so this doesn't enable strict mode
+    assert_eq!(names("'use\\x20strict'"), vec!["use\\x20strict"]);
+    assert_eq!(names("'use strict"), Vec::<String>::new());
+    assert_eq!(names("'use strict\\'\nfoo()"), Vec::<String>::new());
+    assert_eq!(names("'a\\\\'"), vec!["a\\\\"]);
+}
+
+#[test]
+fn hashbang() {
+    assert_eq!(
+        parse_hashbang("#!/usr/bin/env node\nrequire('a')"),
+        Some("/usr/bin/env node".to_string())
+    );
+    assert_eq!(parse_hashbang("#!"), Some("".to_string()));
+    assert_eq!(parse_hashbang("// #!/usr/bin/env node"), None);
+    assert_eq!(
+        guess_js_syntax("#!/usr/bin/env node\nimport 'a'"),
+        JsSyntax::ESM
+    );
+    assert_eq!(
+        guess_js_syntax("<!-- import 'a'\nrequire('b')"),
+        JsSyntax::CJS
+    );
+}
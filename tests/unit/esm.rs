@@ -0,0 +1,127 @@
+use fmu::{parse_esm, EsmExportKind, EsmImportKind, JsDialect};
+
+#[test]
+fn imports() {
+    let s = r#"import foo, { bar as baz } from 'foo'
+import 'side-effect'
+import * as ns from "ns" with { type: 'json' }
+const a = import(`dynamic`)
+const b = import(name)
+console.log(import.meta.url, foo.import)
+// import 'commented'
+"#;
+    let result = parse_esm(s, JsDialect::JS);
+    let imports: Vec<_> = result
+        .imports
+        .iter()
+        .map(|v| (v.kind, v.specifier.as_deref()))
+        .collect();
+    assert_eq!(
+        imports,
+        vec![
+            (EsmImportKind::Static, Some("foo")),
+            (EsmImportKind::Static, Some("side-effect")),
+            (EsmImportKind::Static, Some("ns")),
+            (EsmImportKind::Dynamic, Some("dynamic")),
+            (EsmImportKind::Dynamic, None),
+            (EsmImportKind::Meta, None),
+        ]
+    );
+
+    let first = &result.imports[0];
+    assert_eq!(
+        &s[first.span.start..first.span.end],
+        "import foo, { bar as baz } from 'foo'"
+    );
+    let span = first.specifier_span.unwrap();
+    assert_eq!(&s[span.start..span.end], "foo");
+    let third = &result.imports[2];
+    assert!(s[third.span.start..third.span.end].ends_with("{ type: 'json' }"));
+}
+
+#[test]
+fn exports() {
+    let s = r#"export const a = 1, { b, c: [d] } = obj
+export function* e() {}
+export class F {}
+export { g, h as i }
+export { j as default } from './j'
+export * from './k'
+export * as l from './l'
+export default m
+"#;
+    let result = parse_esm(s, JsDialect::JS);
+    let exports: Vec<_> = result
+        .exports
+        .iter()
+        .map(|v| {
+            (
+                v.kind,
+                v.name.as_deref(),
+                v.local.as_deref(),
+                v.specifier.as_deref(),
+            )
+        })
+        .collect();
+    use EsmExportKind::*;
+    assert_eq!(
+        exports,
+        vec![
+            (Named, Some("a"), None, None),
+            (Named, Some("b"), None, None),
+            (Named, Some("d"), None, None),
+            (Named, Some("e"), None, None),
+            (Named, Some("F"), None, None),
+            (Named, Some("g"), None, None),
+            (Named, Some("i"), Some("h"), None),
+            (Named, Some("default"), Some("j"), Some("./j")),
+            (Star, None, None, Some("./k")),
+            (Named, Some("l"), Some("*"), Some("./l")),
+            (Default, Some("default"), None, None),
+        ]
+    );
+
+    let specifiers: Vec<_> = result
+        .imports
+        .iter()
+        .map(|v| v.specifier.as_deref())
+        .collect();
+    assert_eq!(specifiers, vec![Some("./j"), Some("./k"), Some("./l")]);
+}
+
+#[test]
+fn escaped_specifier() {
+    let result = parse_esm(r"import 'foo\x2Fbar'", JsDialect::JS);
+    assert_eq!(result.imports[0].specifier.as_deref(), Some("foo/bar"));
+    // surrogate pairs are combined, lone surrogates can't be decoded
+    let result = parse_esm(
+        r"import '\uD83D\uDE00'; import '\u{D83D}\u{DE00}'; import '\uD83Dx'",
+        JsDialect::JS,
+    );
+    let specifiers: Vec<_> = result
+        .imports
+        .iter()
+        .map(|v| v.specifier.as_deref())
+        .collect();
+    assert_eq!(
+        specifiers,
+        vec![Some("\u{1F600}"), Some("\u{1F600}"), Some("\u{FFFD}x")]
+    );
+}
+
+#[test]
+fn export_destructuring_literals() {
+    // invalid patterns, but they must not hang
+    let names = |s: &str| -> Vec<String> {
+        parse_esm(s, JsDialect::JS)
+            .exports
+            .into_iter()
+            .filter_map(|v| v.name)
+            .collect()
+    };
+    assert_eq!(names("export const [1] = x"), Vec::<String>::new());
+    assert_eq!(names("export const { a: 1 } = x"), Vec::<String>::new());
+    assert_eq!(names("export const [a, 'b', `c`, d] = x"), vec!["a", "d"]);
+    assert_eq!(names("export const { a: \"x\", b } = y"), vec!["b"]);
+    assert_eq!(names("export const [/a/, b] = x"), vec!["b"]);
+}
@@ -0,0 +1,248 @@
+use fmu::{
+    parse_json, resolve_package, resolve_package_exports, resolve_package_imports,
+    resolve_package_specifier, JsResolveError,
+};
+
+const PACKAGE_JSON: &str = r##"{
+  "name": "@scope/pkg",
+  "exports": {
+    ".": {
+      "types": "./dist/index.d.ts",
+      "import": "./dist/index.mjs",
+      "require": "./dist/index.cjs"
+    },
+    "./features/*.js": {
+      "browser": "./dist/features/*.browser.js",
+      "default": "./dist/features/*.js"
+    },
+    "./features/internal/*": null,
+    "./utils": ["./dist/utils.js"],
+    "./package.json": "./package.json"
+  },
+  "imports": {
+    "#dep": {
+      "node": "dep-node-native",
+      "default": "./dep-polyfill.js"
+    },
+    "#internal/*.js": "./src/internal/*.js"
+  }
+}"##;
+
+#[test]
+fn exports() {
+    let json = parse_json(PACKAGE_JSON).unwrap();
+    let resolve =
+        |subpath: &str, conditions: &[&str]| resolve_package_exports(&json, subpath, conditions);
+    assert_eq!(
+        resolve(".", &["node", "import"]),
+        Ok("./dist/index.mjs".to_string())
+    );
+    assert_eq!(
+        resolve(".", &["node", "require"]),
+        Ok("./dist/index.cjs".to_string())
+    );
+    // the order of the conditions in package.json decides, not the order they're given in
+    assert_eq!(
+        resolve(".", &["require", "types"]),
+        Ok("./dist/index.d.ts".to_string())
+    );
+    assert_eq!(
+        resolve(".", &["node"]),
+        Err(JsResolveError::PackagePathNotExported)
+    );
+    assert_eq!(
+        resolve("./features/a/b.js", &["browser"]),
+        Ok("./dist/features/a/b.browser.js".to_string())
+    );
+    assert_eq!(
+        resolve("./features/a.js", &[]),
+        Ok("./dist/features/a.js".to_string())
+    );
+    assert_eq!(
+        resolve("./features/internal/a.js", &[]),
+        Err(JsResolveError::PackagePathNotExported)
+    );
+    assert_eq!(resolve("./utils", &[]), Ok("./dist/utils.js".to_string()));
+    assert_eq!(
+        resolve("./dist/index.mjs", &["import"]),
+        Err(JsResolveError::PackagePathNotExported)
+    );
+    assert_eq!(
+        resolve("./features/../../secret.js", &[]),
+        Err(JsResolveError::InvalidModuleSpecifier)
+    );
+    assert_eq!(
+        resolve("features", &[]),
+        Err(JsResolveError::InvalidModuleSpecifier)
+    );
+}
+
+#[test]
+fn exports_sugar() {
+    let resolve = |package_json: &str, subpath: &str| {
+        resolve_package_exports(&parse_json(package_json).unwrap(), subpath, &["import"])
+    };
+    assert_eq!(
+        resolve(r##"{ "exports": "./index.js" }"##, "."),
+        Ok("./index.js".to_string())
+    );
+    assert_eq!(
+        resolve(
+            r##"{ "exports": { "import": "./a.mjs", "default": "./a.js" } }"##,
+            "."
+        ),
+        Ok("./a.mjs".to_string())
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": "./index.js" }"##, "./index.js"),
+        Err(JsResolveError::PackagePathNotExported)
+    );
+    // without `exports`, everything is exported
+    assert_eq!(
+        resolve(r##"{ "main": "lib/index.js" }"##, "."),
+        Ok("./lib/index.js".to_string())
+    );
+    assert_eq!(resolve("{}", "."), Ok("./index.js".to_string()));
+    assert_eq!(resolve("{}", "./a.js"), Ok("./a.js".to_string()));
+}
+
+#[test]
+fn exports_invalid() {
+    let resolve = |package_json: &str, subpath: &str| {
+        resolve_package_exports(&parse_json(package_json).unwrap(), subpath, &["import"])
+    };
+    assert_eq!(
+        resolve(
+            r##"{ "exports": { ".": "./a.js", "import": "./b.js" } }"##,
+            "."
+        ),
+        Err(JsResolveError::InvalidPackageConfig)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": { "0": "./a.js" } }"##, "."),
+        Err(JsResolveError::InvalidPackageConfig)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": "index.js" }"##, "."),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": "./node_modules/a/index.js" }"##, "."),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": "./a/%2E%2e/b.js" }"##, "."),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": 1 }"##, "."),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+    // invalid targets in arrays fall back to the next one
+    assert_eq!(
+        resolve(r##"{ "exports": ["../a.js", "./b.js"] }"##, "."),
+        Ok("./b.js".to_string())
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": ["../a.js"] }"##, "."),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+    assert_eq!(
+        resolve(r##"{ "exports": [] }"##, "."),
+        Err(JsResolveError::PackagePathNotExported)
+    );
+}
+
+#[test]
+fn exports_pattern_order() {
+    let json = parse_json(
+        r##"{
+          "exports": {
+            "./*": "./dist/*",
+            "./a/*": "./a/*.js",
+            "./a/*.js": "./a/*.mjs",
+            "./a/b": "./b.js"
+          }
+        }"##,
+    )
+    .unwrap();
+    let resolve = |subpath: &str| resolve_package_exports(&json, subpath, &[]);
+    assert_eq!(resolve("./a/b"), Ok("./b.js".to_string()));
+    assert_eq!(resolve("./a/c.js"), Ok("./a/c.mjs".to_string()));
+    assert_eq!(resolve("./a/c"), Ok("./a/c.js".to_string()));
+    assert_eq!(resolve("./c"), Ok("./dist/c".to_string()));
+    assert_eq!(resolve("./"), Err(JsResolveError::PackagePathNotExported));
+    // node only deprecates empty segments
+    assert_eq!(resolve("./c//d"), Ok("./dist/c//d".to_string()));
+}
+
+#[test]
+fn imports() {
+    let json = parse_json(PACKAGE_JSON).unwrap();
+    let resolve = |specifier: &str, conditions: &[&str]| {
+        resolve_package_imports(&json, specifier, conditions)
+    };
+    assert_eq!(
+        resolve("#dep", &["node"]),
+        Ok("dep-node-native".to_string())
+    );
+    assert_eq!(resolve("#dep", &[]), Ok("./dep-polyfill.js".to_string()));
+    assert_eq!(
+        resolve("#internal/a.js", &[]),
+        Ok("./src/internal/a.js".to_string())
+    );
+    assert_eq!(
+        resolve("#missing", &[]),
+        Err(JsResolveError::PackageImportNotDefined)
+    );
+    assert_eq!(
+        resolve("#/a", &[]),
+        Err(JsResolveError::InvalidModuleSpecifier)
+    );
+    assert_eq!(
+        resolve("#internal/", &[]),
+        Err(JsResolveError::InvalidModuleSpecifier)
+    );
+    assert_eq!(
+        resolve_package_imports(
+            &parse_json(r##"{ "imports": { "#a": "https://example.com/a.js" } }"##).unwrap(),
+            "#a",
+            &[]
+        ),
+        Err(JsResolveError::InvalidPackageTarget)
+    );
+}
+
+#[test]
+fn specifier() {
+    let json = parse_json(PACKAGE_JSON).unwrap();
+    let resolve = |specifier: &str| resolve_package_specifier(&json, specifier, &["import"]);
+    assert_eq!(resolve("@scope/pkg"), Ok("./dist/index.mjs".to_string()));
+    assert_eq!(
+        resolve("@scope/pkg/package.json"),
+        Ok("./package.json".to_string())
+    );
+    assert_eq!(resolve("#dep"), Ok("./dep-polyfill.js".to_string()));
+    assert_eq!(
+        resolve("@scope"),
+        Err(JsResolveError::InvalidModuleSpecifier)
+    );
+    assert_eq!(resolve(""), Err(JsResolveError::InvalidModuleSpecifier));
+    // relative and absolute specifiers, and names starting with `.` or containing `\` or `%`
+    for specifier in [
+        "./utils", "../foo", ".", "/foo", ".p/foo", "p\\foo", "p%2Ffoo",
+    ] {
+        assert_eq!(
+            resolve(specifier),
+            Err(JsResolveError::InvalidModuleSpecifier),
+            "{specifier}"
+        );
+    }
+
+    let resolution = resolve_package(PACKAGE_JSON, "@scope/pkg", vec!["require".to_string()]);
+    assert_eq!(resolution.target.as_deref(), Some("./dist/index.cjs"));
+    assert_eq!(resolution.error, None);
+    let resolution = resolve_package("{", "pkg", vec![]);
+    assert_eq!(resolution.target, None);
+    assert_eq!(resolution.error, Some(JsResolveError::InvalidPackageConfig));
+}
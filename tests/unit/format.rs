@@ -0,0 +1,40 @@
+use crate::rs;
+use fmu::{analyze_js_syntax, guess_js_format, guess_js_syntax, JsFormat, JsSyntax};
+
+#[test]
+fn umd() {
+    let s = rs("format_umd");
+    assert_eq!(guess_js_format(&s), JsFormat::UMD);
+    assert_eq!(guess_js_syntax(&s), JsSyntax::CJS);
+}
+
+#[test]
+fn amd() {
+    let s = rs("format_amd");
+    assert_eq!(guess_js_format(&s), JsFormat::AMD);
+    assert_eq!(guess_js_syntax(&s), JsSyntax::Unknown);
+}
+
+#[test]
+fn system() {
+    assert_eq!(guess_js_format(&rs("format_system")), JsFormat::SystemJS);
+}
+
+#[test]
+fn iife() {
+    assert_eq!(guess_js_format(&rs("format_iife")), JsFormat::IIFE);
+    assert_eq!(guess_js_format("!function () {}()"), JsFormat::IIFE);
+    assert_eq!(guess_js_format("((root) => {})(this)"), JsFormat::IIFE);
+    assert_eq!(guess_js_format("(foo)()"), JsFormat::Unknown);
+}
+
+#[test]
+fn esm_and_cjs() {
+    assert_eq!(guess_js_format("export default 1"), JsFormat::ESM);
+    assert_eq!(
+        guess_js_format("(function () { exports.a = 1 })()"),
+        JsFormat::CJS
+    );
+    assert_eq!(guess_js_format("foo.define(() => {})"), JsFormat::Unknown);
+    assert_eq!(analyze_js_syntax(&rs("mixed")).format, JsFormat::Mixed);
+}
@@ -0,0 +1,137 @@
+use fmu::{check_package_hazards, JsSyntax, PackageHazardKind};
+use std::collections::HashMap;
+
+fn check(package_json: &str, files: &[(&str, &str)]) -> Vec<(PackageHazardKind, String)> {
+    let files: HashMap<&str, &str> = files.iter().copied().collect();
+    check_package_hazards(package_json, |path| files.get(path).map(|s| s.to_string()))
+        .unwrap()
+        .into_iter()
+        .map(|h| (h.kind, h.subpath))
+        .collect()
+}
+
+#[test]
+fn dual_package() {
+    let package_json = r#"{
+      "exports": {
+        ".": { "import": "./index.mjs", "require": "./index.cjs" },
+        "./wrapped": { "import": "./esm/wrapper.mjs", "require": "./cjs/index.cjs" }
+      }
+    }"#;
+    let hazards = check_package_hazards(package_json, |path| match path {
+        "./index.mjs" => Some("export default 1".to_string()),
+        "./index.cjs" => Some("module.exports = 1".to_string()),
+        // the esm wrapper only re-exports the cjs file, so it's loaded once
+        "./esm/wrapper.mjs" => Some("export { default } from '../cjs/index.cjs'".to_string()),
+        "./cjs/index.cjs" => Some("module.exports = 1".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    assert_eq!(hazards.len(), 1);
+    assert_eq!(hazards[0].kind, PackageHazardKind::DualPackage);
+    assert_eq!(hazards[0].subpath, ".");
+    assert_eq!(hazards[0].import.as_deref(), Some("./index.mjs"));
+    assert_eq!(hazards[0].require.as_deref(), Some("./index.cjs"));
+}
+
+#[test]
+fn wrong_format() {
+    let package_json = r#"{
+      "type": "module",
+      "exports": {
+        "import": "./dist/index.js",
+        "require": "./dist/cjs/index.js"
+      }
+    }"#;
+    // the nested package.json makes `dist/cjs` commonjs, but the code is esm
+    let hazards = check_package_hazards(package_json, |path| {
+        let s = match path {
+            "./dist/index.js" => "module.exports = 1",
+            "./dist/cjs/index.js" => "export default 1",
+            "./dist/cjs/package.json" => r#"{ "type": "commonjs" }"#,
+            _ => return None,
+        };
+        Some(s.to_string())
+    })
+    .unwrap();
+    let kinds: Vec<_> = hazards.iter().map(|h| h.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            PackageHazardKind::DualPackage,
+            PackageHazardKind::RequireEsm,
+            PackageHazardKind::ImportCjs
+        ]
+    );
+    let require = hazards[1].format.as_ref().unwrap();
+    assert_eq!(
+        (require.format, require.syntax),
+        (JsSyntax::CJS, JsSyntax::ESM)
+    );
+    assert_eq!(hazards[1].require.as_deref(), Some("./dist/cjs/index.js"));
+    let import = hazards[2].format.as_ref().unwrap();
+    assert_eq!(
+        (import.format, import.syntax),
+        (JsSyntax::ESM, JsSyntax::CJS)
+    );
+}
+
+#[test]
+fn wrong_format_default() {
+    // `require` falls back to `default`, which is esm under `"type": "module"`
+    assert_eq!(
+        check(
+            r#"{ "type": "module", "exports": { "import": "./dist/index.js", "default": "./dist/index.cjs.js" } }"#,
+            &[
+                ("./dist/index.js", "export default 1"),
+                ("./dist/index.cjs.js", "module.exports = 1")
+            ]
+        ),
+        vec![
+            (PackageHazardKind::DualPackage, ".".to_string()),
+            (PackageHazardKind::RequireEsm, ".".to_string())
+        ]
+    );
+}
+
+#[test]
+fn single_format() {
+    // esm or cjs only packages aren't reported
+    assert_eq!(
+        check(
+            r#"{ "type": "module", "exports": "./index.js" }"#,
+            &[("./index.js", "export default 1")]
+        ),
+        vec![]
+    );
+    assert_eq!(
+        check(
+            r#"{ "exports": { ".": { "node": "./index.js", "default": "./browser.js" } } }"#,
+            &[("./index.js", "module.exports = 1")]
+        ),
+        vec![]
+    );
+    assert_eq!(check(r#"{ "main": "./index.js" }"#, &[]), vec![]);
+    assert_eq!(
+        check(
+            r#"{ "exports": { "import": "./index.mjs" } }"#,
+            &[("./index.mjs", "export default 1")]
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn missing_file() {
+    assert_eq!(
+        check(
+            r#"{ "exports": { "./a": { "import": "./a.mjs", "require": "./a.cjs" }, "./b": "./b.js", "./*": "./*.js" } }"#,
+            &[("./a.cjs", "module.exports = 1")]
+        ),
+        vec![
+            (PackageHazardKind::MissingFile, "./a".to_string()),
+            (PackageHazardKind::MissingFile, "./b".to_string())
+        ]
+    );
+    assert!(check_package_hazards("{", |_| None).is_none());
+}
@@ -0,0 +1,41 @@
+use fmu::{parse_json, JsonValue};
+
+#[test]
+fn json() {
+    let json = parse_json(
+        "\u{FEFF}{ \"a\": [1, -2.5e3, true, false, null], \"b\": \"\\u00e9\\ud83d\\ude00\\n\", \"a\": {} }",
+    )
+    .unwrap();
+    assert_eq!(json.get("a"), Some(&JsonValue::Object(vec![])));
+    assert_eq!(json.get("b").and_then(JsonValue::as_str), Some("é😀\n"));
+    let JsonValue::Object(entries) = &json else {
+        panic!("not an object");
+    };
+    assert_eq!(
+        entries[0].1,
+        JsonValue::Array(vec![
+            JsonValue::Number(1.0),
+            JsonValue::Number(-2500.0),
+            JsonValue::Bool(true),
+            JsonValue::Bool(false),
+            JsonValue::Null,
+        ])
+    );
+}
+
+#[test]
+fn json_invalid() {
+    for s in [
+        "",
+        "{",
+        "{\"a\" 1}",
+        "[1,]",
+        "{\"a\": 1} x",
+        "\"a\nb\"",
+        "\"\\x\"",
+        "nul",
+    ] {
+        assert_eq!(parse_json(s), None, "{}", s);
+    }
+    assert_eq!(parse_json(&"[".repeat(100_000)), None);
+}
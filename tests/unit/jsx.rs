@@ -0,0 +1,79 @@
+use crate::rs_ext;
+use fmu::{analyze_js_syntax_with_dialect, guess_js_syntax_with_dialect};
+use fmu::{parse_esm, parse_requires, rewrite_specifiers};
+use fmu::{JsDiagnosticKind, JsDialect, JsSyntax, Lexer, TokenKind};
+
+#[test]
+fn text() {
+    let s = rs_ext("jsx_text", "jsx");
+    assert_eq!(
+        guess_js_syntax_with_dialect(&s, JsDialect::JSX),
+        JsSyntax::ESM
+    );
+    assert!(analyze_js_syntax_with_dialect(&s, JsDialect::JSX)
+        .diagnostics
+        .is_empty());
+    // without jsx mode, the quote in `don't` is an unterminated string
+    let diagnostics = analyze_js_syntax_with_dialect(&s, JsDialect::JS).diagnostics;
+    assert_eq!(diagnostics[0].kind, JsDiagnosticKind::UnterminatedString);
+    assert_eq!(diagnostics[0].span.line, 3);
+}
+
+#[test]
+fn tsx_generics() {
+    let s = rs_ext("tsx_generics", "tsx");
+    assert_eq!(
+        guess_js_syntax_with_dialect(&s, JsDialect::TSX),
+        JsSyntax::ESM
+    );
+}
+
+#[test]
+fn comparison() {
+    let s = "if (a < b && c > d) {}\nconst x = y <z\nexport {}";
+    assert_eq!(
+        guess_js_syntax_with_dialect(s, JsDialect::JSX),
+        JsSyntax::ESM
+    );
+}
+
+#[test]
+fn tokens() {
+    let s = "x = <a b={c}>d</a>";
+    let tokens: Vec<_> = Lexer::with_dialect(s, JsDialect::JSX)
+        .map(|t| (t.kind, t.text(s)))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Identifier, "x"),
+            (TokenKind::Punctuator, "="),
+            (TokenKind::Jsx, "<"),
+            (TokenKind::Jsx, "a b="),
+            (TokenKind::Jsx, "{"),
+            (TokenKind::Identifier, "c"),
+            (TokenKind::Jsx, "}"),
+            (TokenKind::Jsx, ">"),
+            (TokenKind::Jsx, "d"),
+            (TokenKind::Jsx, "</"),
+            (TokenKind::Jsx, "a"),
+            (TokenKind::Jsx, ">"),
+        ]
+    );
+}
+
+#[test]
+fn parse_after_markup() {
+    // the backticks in the text would start a template literal outside of jsx
+    let s = "const a = <code>`npm i`</code>;\nconst b = <p>Use ` to quote</p>;\nimport x from 'y';\nconst c = require('z');";
+    for dialect in [JsDialect::JSX, JsDialect::TSX] {
+        let imports = parse_esm(s, dialect).imports;
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].specifier.as_deref(), Some("y"));
+        let requires = parse_requires(s, dialect);
+        assert_eq!(requires.len(), 1);
+        assert_eq!(requires[0].specifier.as_deref(), Some("z"));
+        let rewritten = rewrite_specifiers(s, dialect, |v| Some(format!("./{}", v)));
+        assert!(rewritten.ends_with("import x from './y';\nconst c = require('./z');"));
+    }
+}
@@ -0,0 +1,105 @@
+use fmu::{Lexer, LineIndex, TokenKind};
+
+// lex into (kind, text) pairs so assertions are easy to read
+fn lex(s: &str) -> Vec<(TokenKind, &str)> {
+    Lexer::new(s).map(|t| (t.kind, t.text(s))).collect()
+}
+
+#[test]
+fn tokens() {
+    use TokenKind::*;
+    assert_eq!(
+        lex("import foo from 'bar' // hi\nconst a = 1.5e+3 >>>= b?.c"),
+        vec![
+            (Identifier, "import"),
+            (Identifier, "foo"),
+            (Identifier, "from"),
+            (String, "'bar'"),
+            (Comment, "// hi"),
+            (Identifier, "const"),
+            (Identifier, "a"),
+            (Punctuator, "="),
+            (Number, "1.5e+3"),
+            (Punctuator, ">>>="),
+            (Identifier, "b"),
+            (Punctuator, "?."),
+            (Identifier, "c"),
+        ]
+    );
+}
+
+#[test]
+fn template_and_regex() {
+    use TokenKind::*;
+    assert_eq!(
+        lex("x = `a${b}c` /* d */ ; /e\\//g.test(f) / 2\n"),
+        vec![
+            (Identifier, "x"),
+            (Punctuator, "="),
+            (Template, "`a${"),
+            (Identifier, "b"),
+            (Template, "}c`"),
+            (Comment, "/* d */"),
+            (Punctuator, ";"),
+            (Regex, "/e\\//g"),
+            (Punctuator, "."),
+            (Identifier, "test"),
+            (Punctuator, "("),
+            (Identifier, "f"),
+            (Punctuator, ")"),
+            (Punctuator, "/"),
+            (Number, "2"),
+        ]
+    );
+}
+
+#[test]
+fn spans() {
+    let s = "const é = 'ü'\n";
+    let tokens: Vec<_> = Lexer::new(s).collect();
+    assert_eq!(tokens[1].text(s), "é");
+    assert_eq!((tokens[3].start, tokens[3].end), (11, 15));
+}
+
+#[test]
+fn hashbang_and_html_comments() {
+    use TokenKind::*;
+    assert_eq!(
+        lex("#!/usr/bin/env node\n<!-- 'hidden\n  --> a = 'b\nx-->y"),
+        vec![
+            (Comment, "#!/usr/bin/env node"),
+            (Comment, "<!-- 'hidden"),
+            (Comment, "--> a = 'b"),
+            (Identifier, "x"),
+            (Punctuator, "--"),
+            (Punctuator, ">"),
+            (Identifier, "y"),
+        ]
+    );
+    // a hashbang is only at the very start
+    assert_eq!(lex(" #!a")[0], (Identifier, "#"));
+}
+
+#[test]
+fn line_columns() {
+    // é is 2 bytes and 1 utf-16 unit, 😀 is 4 bytes and 2 utf-16 units
+    let s = "'é😀'\n  foo";
+    let spans: Vec<_> = Lexer::new(s)
+        .spanned()
+        .map(|(t, span)| {
+            (
+                t.text(s),
+                span.utf16_start,
+                span.utf16_end,
+                span.line,
+                span.column,
+            )
+        })
+        .collect();
+    assert_eq!(spans, vec![("'é😀'", 0, 5, 1, 0), ("foo", 8, 11, 2, 2)]);
+
+    let index = LineIndex::new(s);
+    let token = Lexer::new(s).nth(1).unwrap();
+    assert_eq!((token.start, token.end), (11, 14));
+    assert_eq!(token.span(&index).utf16_start, 8);
+}
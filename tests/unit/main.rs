@@ -1,4 +1,30 @@
-use fmu::{guess_js_syntax, JsSyntax};
+mod bytes;
+mod cjs;
+#[cfg(feature = "scan")]
+mod cli;
+mod comment;
+mod confidence;
+mod diagnostic;
+mod directive;
+mod esm;
+mod exports;
+mod format;
+mod hazard;
+mod json;
+mod jsx;
+mod lexer;
+mod node;
+mod position;
+mod rewrite;
+#[cfg(feature = "scan")]
+mod scan;
+mod scope;
+mod source_map;
+mod stream;
+mod totality;
+mod ts;
+
+use fmu::{analyze_js_syntax, guess_js_syntax, JsSyntax, JsSyntaxEvidenceKind};
 use std::fs;
 
 #[test]
@@ -9,6 +35,51 @@ fn esm() {
     assert_eq!(guess_js_syntax(&rs("esm_import_meta")), JsSyntax::ESM);
     assert_eq!(guess_js_syntax(&rs("esm_create_require")), JsSyntax::ESM);
     assert_eq!(guess_js_syntax(&rs("esm_entice_cjs")), JsSyntax::ESM);
+    assert_eq!(guess_js_syntax(&rs("esm_top_level_await")), JsSyntax::ESM);
+}
+
+#[test]
+fn esm_syntax_variants() {
+    let kinds = |s: &str| -> Vec<_> {
+        analyze_js_syntax(s)
+            .evidence
+            .iter()
+            .map(|e| e.kind)
+            .collect()
+    };
+    assert_eq!(
+        kinds("import . meta.url"),
+        vec![JsSyntaxEvidenceKind::ImportMeta]
+    );
+    assert_eq!(kinds("import.meta"), vec![JsSyntaxEvidenceKind::ImportMeta]);
+    assert_eq!(
+        kinds("import\r\n  foo\r\nfrom 'foo'"),
+        vec![JsSyntaxEvidenceKind::Import]
+    );
+    assert_eq!(kinds("import\r\n('foo')"), vec![]);
+    assert_eq!(
+        kinds("const a = { import: 1, export: 2 }\na.export = 3"),
+        vec![]
+    );
+    assert_eq!(
+        kinds("if (a) { for await (const b of c) {} }"),
+        vec![JsSyntaxEvidenceKind::TopLevelAwait]
+    );
+    assert_eq!(kinds("const f = async () => await g()"), vec![]);
+    assert_eq!(kinds("class A { async b() { await c } }"), vec![]);
+    for s in [
+        "await 1;",
+        "await -x;",
+        "await !x;",
+        "await ~x;",
+        "await (x);",
+        "await [x];",
+    ] {
+        assert_eq!(kinds(s), vec![JsSyntaxEvidenceKind::TopLevelAwait], "{}", s);
+    }
+    // `await` is an identifier in scripts
+    assert_eq!(kinds("var await = 1; await;"), vec![]);
+    assert_eq!(kinds("await++; await -= 1; await != 1;"), vec![]);
 }
 
 #[test]
@@ -38,6 +109,31 @@ fn iter() {
     assert_eq!(guess_js_syntax(&rs("iter_regex_ambiguity")), JsSyntax::ESM);
 }
 
+#[test]
+fn evidence() {
+    let analysis = analyze_js_syntax(&rs("mixed"));
+    assert_eq!(analysis.syntax, JsSyntax::Mixed);
+    let kinds: Vec<_> = analysis.evidence.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        // `exports` in `module.exports` is a property, not the global
+        vec![JsSyntaxEvidenceKind::Import, JsSyntaxEvidenceKind::Module]
+    );
+    let module = &analysis.evidence[1];
+    assert_eq!((module.span.line, module.span.column), (2, 0));
+    assert_eq!(module.snippet, "module.exports = foo");
+}
+
+#[test]
+fn evidence_ignores_shadowed() {
+    let analysis = analyze_js_syntax(&rs("esm_create_require"));
+    assert_eq!(analysis.syntax, JsSyntax::ESM);
+    assert!(analysis
+        .evidence
+        .iter()
+        .all(|e| e.kind.syntax() == JsSyntax::ESM));
+}
+
 #[test]
 fn quick() {
     assert_eq!(guess_js_syntax(" n     require     "), JsSyntax::CJS);
@@ -45,7 +141,12 @@ fn quick() {
 
 // read sample. shorten so assertions are all single-line.
 fn rs(name: &str) -> String {
-    let s = match fs::read_to_string(format!("tests/unit/samples/{}.js", name)) {
+    rs_ext(name, "js")
+}
+
+// read a sample with another extension, e.g. `ts` or `jsx`
+fn rs_ext(name: &str, extension: &str) -> String {
+    let s = match fs::read_to_string(format!("tests/unit/samples/{}.{}", name, extension)) {
         Err(err) => panic!("Couldn't open file: {}", err),
         Ok(value) => value,
     };
@@ -0,0 +1,137 @@
+use fmu::{
+    check_node_format, check_node_format_file, find_package_json, resolve_node_format,
+    JsNodeFormatReason, JsSyntax,
+};
+use std::fs;
+
+#[test]
+fn node_format() {
+    use JsNodeFormatReason::*;
+    let module = Some(r#"{ "type": "module" }"#);
+    let commonjs = Some(r#"{ "type": "commonjs" }"#);
+    assert_eq!(
+        resolve_node_format("a.mjs", commonjs),
+        Some((JsSyntax::ESM, Extension))
+    );
+    assert_eq!(
+        resolve_node_format("a.cts", module),
+        Some((JsSyntax::CJS, Extension))
+    );
+    assert_eq!(
+        resolve_node_format("a/b.js", module),
+        Some((JsSyntax::ESM, PackageType))
+    );
+    assert_eq!(
+        resolve_node_format("a.ts", commonjs),
+        Some((JsSyntax::CJS, PackageType))
+    );
+    assert_eq!(
+        resolve_node_format("a.js", None),
+        Some((JsSyntax::CJS, Default))
+    );
+    assert_eq!(
+        resolve_node_format("a.js", Some("{}")),
+        Some((JsSyntax::CJS, Default))
+    );
+    assert_eq!(resolve_node_format("a.json", module), None);
+    assert_eq!(resolve_node_format("a.jsx", module), None);
+    assert_eq!(resolve_node_format("a.tsx", None), None);
+}
+
+#[test]
+fn node_format_mismatch() {
+    let check = |path: &str, s: &str, package_json: Option<&str>| {
+        check_node_format(path, s, package_json.map(str::to_string)).map(|f| (f.syntax, f.mismatch))
+    };
+    let module = Some(r#"{ "type": "module" }"#);
+    let commonjs = Some(r#"{ "type": "commonjs" }"#);
+    assert_eq!(
+        check("a.js", "export default 1", commonjs),
+        Some((JsSyntax::ESM, true))
+    );
+    assert_eq!(
+        check("a.js", "export default 1", module),
+        Some((JsSyntax::ESM, false))
+    );
+    assert_eq!(
+        check("a.js", "module.exports = 1", module),
+        Some((JsSyntax::CJS, true))
+    );
+    assert_eq!(
+        check("a.cjs", "module.exports = 1", module),
+        Some((JsSyntax::CJS, false))
+    );
+    assert_eq!(
+        check("a.mjs", "foo()", None),
+        Some((JsSyntax::Unknown, false))
+    );
+    // typescript follows the same rules
+    assert_eq!(
+        check("a.ts", "import a from 'a'", commonjs),
+        Some((JsSyntax::ESM, true))
+    );
+    assert_eq!(
+        check("a.mts", "import a = require('a')", None),
+        Some((JsSyntax::CJS, true))
+    );
+}
+
+#[test]
+fn node_format_detection() {
+    let check = |path: &str, s: &str, package_json: Option<&str>| {
+        check_node_format(path, s, package_json.map(str::to_string))
+            .map(|f| (f.format, f.reason, f.mismatch))
+    };
+    // without `"type"`, node loads esm syntax as esm
+    assert_eq!(
+        check("a.js", "export default 1", None),
+        Some((JsSyntax::ESM, JsNodeFormatReason::Default, false))
+    );
+    assert_eq!(
+        check("a.ts", "import a from 'a'", Some("{}")),
+        Some((JsSyntax::ESM, JsNodeFormatReason::Default, false))
+    );
+    // but `require` isn't defined then
+    assert_eq!(
+        check("a.js", "import a from 'a'\nrequire('b')", None),
+        Some((JsSyntax::ESM, JsNodeFormatReason::Default, true))
+    );
+    assert_eq!(
+        check("a.js", "module.exports = 1", None),
+        Some((JsSyntax::CJS, JsNodeFormatReason::Default, false))
+    );
+    assert_eq!(check("a.jsx", "export default <a />", None), None);
+    assert_eq!(check("a.tsx", "export default <a />", None), None);
+}
+
+#[test]
+fn node_format_file() {
+    let root = std::env::temp_dir().join(format!("fmu-node-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src/node_modules/a/lib")).unwrap();
+    fs::write(root.join("package.json"), r#"{ "type": "module" }"#).unwrap();
+    fs::write(root.join("src/index.js"), "module.exports = 1").unwrap();
+    fs::write(
+        root.join("src/node_modules/a/lib/index.js"),
+        "exports.a = 1",
+    )
+    .unwrap();
+
+    assert_eq!(
+        find_package_json(&root.join("src/index.js")),
+        Some(root.join("package.json"))
+    );
+    let format = check_node_format_file(&root.join("src/index.js"))
+        .unwrap()
+        .unwrap();
+    assert_eq!((format.format, format.mismatch), (JsSyntax::ESM, true));
+
+    // the package.json above node_modules doesn't apply
+    let path = root.join("src/node_modules/a/lib/index.js");
+    assert_eq!(find_package_json(&path), None);
+    let format = check_node_format_file(&path).unwrap().unwrap();
+    assert_eq!((format.format, format.mismatch), (JsSyntax::CJS, false));
+
+    assert!(check_node_format_file(&root.join("missing.js")).is_err());
+    fs::remove_dir_all(root).unwrap();
+}
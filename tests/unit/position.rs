@@ -0,0 +1,41 @@
+use fmu::{parse_esm, JsDialect, LineIndex};
+
+#[test]
+fn ascii() {
+    let index = LineIndex::new("foo\nbar\r\nbaz\rqux");
+    assert_eq!(index.line_column(0), (1, 0));
+    assert_eq!(index.line_column(5), (2, 1));
+    assert_eq!(index.line_column(9), (3, 0));
+    assert_eq!(index.line_column(13), (4, 0));
+    assert_eq!(index.utf16_offset(13), 13);
+}
+
+#[test]
+fn utf16() {
+    // é is 2 bytes and 1 utf-16 unit, 😀 is 4 bytes and 2 utf-16 units
+    let s = "é😀\n// 😀\nimport 'a'";
+    let index = LineIndex::new(s);
+    assert_eq!(index.utf16_offset(2), 1);
+    assert_eq!(index.utf16_offset(6), 3);
+    assert_eq!(index.line_column(15), (3, 0));
+
+    let span = parse_esm(s, JsDialect::JS).imports[0]
+        .specifier_span
+        .unwrap();
+    assert_eq!(&s[span.start..span.end], "a");
+    let utf16: Vec<u16> = s.encode_utf16().collect();
+    assert_eq!(
+        String::from_utf16(&utf16[span.utf16_start..span.utf16_end]).unwrap(),
+        "a"
+    );
+    assert_eq!((span.line, span.column), (3, 8));
+}
+
+#[test]
+fn long_line() {
+    // offsets far from a line start still map correctly
+    let s = "é".repeat(1000) + "x";
+    let index = LineIndex::new(&s);
+    assert_eq!(index.utf16_offset(2000), 1000);
+    assert_eq!(index.line_column(2000), (1, 1000));
+}
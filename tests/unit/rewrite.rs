@@ -0,0 +1,33 @@
+use fmu::{rewrite_specifiers, JsDialect};
+
+#[test]
+fn rewrite() {
+    let s = r#"import foo from 'foo'
+export * from "./bar"
+const baz = await import(`baz`)
+const qux = require('qux') // 'qux'
+import.meta.url
+"#;
+    let result = rewrite_specifiers(s, JsDialect::JS, |specifier| {
+        if specifier.starts_with('.') {
+            None
+        } else {
+            Some(format!("https://esm.sh/{}", specifier))
+        }
+    });
+    assert_eq!(
+        result,
+        r#"import foo from 'https://esm.sh/foo'
+export * from "./bar"
+const baz = await import(`https://esm.sh/baz`)
+const qux = require('https://esm.sh/qux') // 'qux'
+import.meta.url
+"#
+    );
+}
+
+#[test]
+fn rewrite_escapes() {
+    let result = rewrite_specifiers("import 'a'", JsDialect::JS, |_| Some("it's".to_string()));
+    assert_eq!(result, r"import 'it\'s'");
+}
@@ -0,0 +1,5 @@
+const config = await loadConfig()
+
+async function loadConfig() {
+  return await fetch('/config.json')
+}
@@ -0,0 +1,4 @@
+define(['require', 'exports', 'lodash'], function (require, exports, _) {
+  'use strict'
+  exports.foo = _.identity(require('./foo'))
+})
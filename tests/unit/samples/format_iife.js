@@ -0,0 +1,8 @@
+/*! lib v1.0.0 */
+'use strict';
+var Lib = (function () {
+  function define(name) {
+    return name
+  }
+  return { define: define }
+})()
@@ -0,0 +1,14 @@
+System.register(['lodash'], function (exports, module) {
+  'use strict'
+  var _
+  return {
+    setters: [
+      function (m) {
+        _ = m.default
+      }
+    ],
+    execute: function () {
+      exports('foo', _.identity(1))
+    }
+  }
+})
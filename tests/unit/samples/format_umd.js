@@ -0,0 +1,11 @@
+(function (global, factory) {
+  typeof exports === 'object' && typeof module !== 'undefined'
+    ? factory(exports)
+    : typeof define === 'function' && define.amd
+      ? define(['exports'], factory)
+      : ((global = typeof globalThis !== 'undefined' ? globalThis : global || self),
+        factory((global.Lib = {})))
+})(this, function (exports) {
+  'use strict'
+  exports.foo = 'bar'
+})
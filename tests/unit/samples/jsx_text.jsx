@@ -0,0 +1,10 @@
+const App = () => (
+  <div className="app" title='it"s'>
+    <p>don't stop</p>
+    <a href="/docs">/docs and `more`</a>
+    {items.map((item) => <Item key={item.id} {...item} />)}
+    <>{`${count} items`}</>
+  </div>
+)
+
+import React from 'react'
@@ -0,0 +1,14 @@
+declare module 'foo' {
+  export const foo: string
+  import bar from 'bar'
+}
+
+declare global {
+  export const baz: string
+}
+
+namespace Utils.Inner {
+  export const qux = 1
+}
+
+const value = require('value')
@@ -0,0 +1,15 @@
+import type from './type'
+import { type Foo, bar } from './bar'
+
+declare const require: NodeRequire
+
+class Loader {
+  constructor(private require: NodeRequire) {}
+}
+
+const a = foo<Bar>(baz) / 2 / 3
+const b = c[0] / 2 // '
+
+export default function load<T>(x: T): T {
+  return x
+}
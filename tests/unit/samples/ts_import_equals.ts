@@ -0,0 +1,10 @@
+// compiles to `const fs = require('fs')` with `module: commonjs`
+import fs = require('fs')
+import type { Stats } from 'fs'
+import Foo = Bar.Baz
+
+export type Options = { stats: Stats }
+export interface Result {}
+export = function read(options: Options): Result {
+  return fs.readFileSync(options.path)
+}
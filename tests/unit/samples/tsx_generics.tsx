@@ -0,0 +1,5 @@
+const identity = <T,>(value: T) => value
+const first = <T extends unknown[]>(list: T) => list[0]
+const el = <Button onClick={() => identity<string>('/')}>it's</Button>
+
+export default el
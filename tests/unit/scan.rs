@@ -0,0 +1,94 @@
+use fmu::{glob_match, scan_dir, JsSyntax, ScanOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn fixture(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("fmu-scan-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    write(&root, "index.js", "import a from 'a'");
+    write(&root, "test/a.test.js", "require('a')");
+    write(&root, "node_modules/a/index.js", "module.exports = 1");
+    write(&root, "node_modules/a/esm/index.mjs", "export default 1");
+    write(&root, "node_modules/a/README.md", "require('a')");
+    write(&root, "node_modules/@s/b/index.js", "export {}");
+    write(
+        &root,
+        "node_modules/a/node_modules/c/index.cjs",
+        "exports.c = 1",
+    );
+    write(&root, "node_modules/a/dist/a.min.js", "exports.a = 1");
+    root
+}
+
+#[test]
+fn scan() {
+    let root = fixture("all");
+    let options = ScanOptions {
+        exclude: vec!["test/".to_string(), "*.min.js".to_string()],
+        threads: 3,
+    };
+    let result = scan_dir(&root, &options);
+    assert!(result.errors.is_empty());
+    let packages: Vec<_> = result
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.syntax(), p.files.len()))
+        .collect();
+    let root_name = root.file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        packages,
+        vec![
+            (root_name, JsSyntax::ESM, 1),
+            ("@s/b", JsSyntax::ESM, 1),
+            ("a", JsSyntax::Mixed, 2),
+            ("c", JsSyntax::CJS, 1),
+        ]
+    );
+    let a = &result.packages[2];
+    assert_eq!((a.esm, a.cjs), (1, 1));
+    assert_eq!(a.dir, root.join("node_modules/a"));
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn scan_exclude_negated() {
+    let root = fixture("negated");
+    let options = ScanOptions {
+        exclude: vec![
+            "node_modules/".to_string(),
+            "!node_modules/".to_string(),
+            "/index.js".to_string(),
+        ],
+        threads: 0,
+    };
+    let result = scan_dir(&root, &options);
+    let names: Vec<_> = result.packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names.len(), 4);
+    assert!(result.packages[0]
+        .files
+        .iter()
+        .all(|f| !f.path.ends_with("index.js")));
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn glob() {
+    assert!(glob_match("src/**/*.js", "src/a.js"));
+    assert!(glob_match("src/**/*.js", "src/a/b/c.js"));
+    assert!(!glob_match("src/**/*.js", "lib/a.js"));
+    assert!(glob_match("**/test/**", "a/test/b/c.js"));
+    assert!(glob_match("*.min.js", "a.min.js"));
+    assert!(!glob_match("*.min.js", "a/b.min.js"));
+    assert!(glob_match("a?c/*", "abc/d"));
+    assert!(!glob_match("a?c", "ac"));
+    // wildcards are backtracked in linear steps, not exponential ones
+    let path = format!("{}/{}", "a/".repeat(50), "a".repeat(100));
+    assert!(!glob_match("**/a*a*a*a*a*a*a*a*b", &path));
+    assert!(!glob_match("**/**/**/**/**/**/b", &path));
+}
@@ -0,0 +1,183 @@
+use fmu::{guess_js_syntax, guess_js_syntax_with_dialect, parse_cjs, parse_requires};
+use fmu::{JsDialect, JsSyntax};
+use std::time::Instant;
+
+#[test]
+fn arrow_params() {
+    assert_eq!(
+        guess_js_syntax("const f = (require) => require('x')"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("const f = require => require('x')"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("const f = async ({ module }) => { module.exports = 1 }"),
+        JsSyntax::Unknown
+    );
+    // the concise body ends at the comma
+    assert_eq!(
+        guess_js_syntax("foo((require) => require('x'), require('y'))"),
+        JsSyntax::CJS
+    );
+}
+
+#[test]
+fn catch_param() {
+    assert_eq!(
+        guess_js_syntax("try {} catch (module) { module.exports = 1 }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("try {} catch ({ exports }) {}\nexports.a = 1"),
+        JsSyntax::CJS
+    );
+}
+
+#[test]
+fn destructuring() {
+    assert_eq!(
+        guess_js_syntax("const { exports, module: [module] } = x\nexports.a = module"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("for (const { require } of list) require('a')\nrequire('b')"),
+        JsSyntax::CJS
+    );
+}
+
+#[test]
+fn functions_and_classes() {
+    assert_eq!(
+        guess_js_syntax("function require(id) {}\nrequire('x')"),
+        JsSyntax::Unknown
+    );
+    // function expression names are only visible within
+    assert_eq!(
+        guess_js_syntax("const f = function require() { require('a') }\nrequire('b')"),
+        JsSyntax::CJS
+    );
+    assert_eq!(
+        guess_js_syntax("class module {}\nmodule.a = 1"),
+        JsSyntax::Unknown
+    );
+    // var is function scoped
+    assert_eq!(
+        guess_js_syntax("function f() { if (a) { var exports = {} } exports.a = 1 }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("function f() { { let exports = {} } exports.a = 1 }"),
+        JsSyntax::CJS
+    );
+}
+
+#[test]
+fn methods_and_keys() {
+    assert_eq!(
+        guess_js_syntax("class A { require(id) { return id } }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess_js_syntax("const a = { get exports() {}, module: 1 }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(guess_js_syntax("const a = { module }"), JsSyntax::CJS);
+    // params of methods shadow too
+    assert_eq!(
+        guess_js_syntax("const a = { load(require) { require('x') } }"),
+        JsSyntax::Unknown
+    );
+}
+
+#[test]
+fn call_arguments() {
+    // not a parameter declaration
+    assert_eq!(guess_js_syntax("console.log(require('x'))"), JsSyntax::CJS);
+    assert_eq!(guess_js_syntax("foo(a, exports)"), JsSyntax::CJS);
+}
+
+#[test]
+fn typescript() {
+    let guess = |s| guess_js_syntax_with_dialect(s, JsDialect::TS);
+    assert_eq!(
+        guess("const f = (require: NodeRequire): void => require('x')"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess("class A { constructor(private module: Module) { module.load() } }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess("function load(require: NodeRequire): string { return require('x') }"),
+        JsSyntax::Unknown
+    );
+    assert_eq!(
+        guess("declare function require(id: string): any\nrequire('x')"),
+        JsSyntax::CJS
+    );
+}
+
+#[test]
+fn call_followed_by_block() {
+    // a call and a block on the next line, not a method
+    assert_eq!(
+        guess_js_syntax("foo(require)\n{\n  require('x')\n}"),
+        JsSyntax::CJS
+    );
+    assert_eq!(
+        guess_js_syntax("foo([1])\n{\n  bar()\n}"),
+        JsSyntax::Unknown
+    );
+}
+
+#[test]
+fn literals_in_patterns() {
+    for s in [
+        "foo([1])\n{\n  bar()\n}",
+        "var [1] = x",
+        "let [0] = 1",
+        "function f([1]) {}",
+        "const {a: \"x\"} = y",
+        "var[`",
+    ] {
+        assert_eq!(guess_js_syntax(s), JsSyntax::Unknown, "{}", s);
+    }
+    assert_eq!(
+        guess_js_syntax("const [1, require] = x\nrequire('a')"),
+        JsSyntax::Unknown
+    );
+}
+
+#[test]
+fn declared_after_use() {
+    // the declaration comes after the reference, and it's hoisted
+    assert_eq!(
+        guess_js_syntax("export default 1\nfunction f() { require('x'); var require }"),
+        JsSyntax::ESM
+    );
+    assert_eq!(
+        guess_js_syntax("export default 1\nmodule.exports = 1\nfunction f(module) {}"),
+        JsSyntax::Mixed
+    );
+}
+
+#[test]
+fn large_bundle() {
+    // webpack-style wrappers shadow `module`, `exports` and `require` in every module, so looking
+    // up a reference must not go through all bindings of the file
+    let module = "(function(module, exports, require) {\n  var a = require('./a');\n  function f(exports) { return exports; }\n  module.exports = { a: a, f: f };\n}),\n";
+    let scan = |n: usize| {
+        let s = format!("var modules = [\n{}];", module.repeat(n));
+        let start = Instant::now();
+        assert_eq!(guess_js_syntax(&s), JsSyntax::Unknown);
+        assert!(parse_requires(&s, JsDialect::JS).is_empty());
+        assert!(parse_cjs(&s, JsDialect::JS).exports.is_empty());
+        start.elapsed()
+    };
+    let small = scan(1000);
+    let large = scan(8000);
+    // linear is 8x, going through all bindings is 64x
+    assert!(large < small * 24, "{:?} vs {:?}", large, small);
+}
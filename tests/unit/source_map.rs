@@ -0,0 +1,50 @@
+use fmu::{parse_source_map_comments, JsDialect, JsSourceMapCommentKind};
+
+#[test]
+fn source_map_comments() {
+    let s = "a()\n//# sourceMappingURL=a.js.map\n//@ sourceURL=a.js\n";
+    let result = parse_source_map_comments(s, JsDialect::JS, true);
+    let comments: Vec<_> = result
+        .comments
+        .iter()
+        .map(|c| (c.kind, c.url.as_str(), c.span.line))
+        .collect();
+    assert_eq!(
+        comments,
+        vec![
+            (JsSourceMapCommentKind::SourceMappingUrl, "a.js.map", 2),
+            (JsSourceMapCommentKind::SourceUrl, "a.js", 3),
+        ]
+    );
+    assert_eq!(result.code.as_deref(), Some("a()\n"));
+    assert_eq!(
+        parse_source_map_comments(s, JsDialect::JS, false).code,
+        None
+    );
+}
+
+#[test]
+fn source_map_comments_not_trailing() {
+    // an inlined dependency's comment, and lookalikes in strings and templates
+    let s = "//# sourceMappingURL=dep.js.map\na('\\\n//# sourceMappingURL=b.js.map')\nc(`\n//# sourceMappingURL=c.js.map\n`)";
+    let result = parse_source_map_comments(s, JsDialect::JS, true);
+    assert!(result.comments.is_empty());
+    assert_eq!(result.code.as_deref(), Some(s));
+}
+
+#[test]
+fn source_map_comments_inline() {
+    // {"version":3}
+    let s = "a()\r\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjozfQ==\r\n";
+    let result = parse_source_map_comments(s, JsDialect::JS, true);
+    assert_eq!(result.comments[0].data.as_deref(), Some("{\"version\":3}"));
+    assert_eq!(result.code.as_deref(), Some("a()\r\n"));
+
+    let s = "//# sourceMappingURL=data:application/json,%7B%22version%22%3A3%7D";
+    let result = parse_source_map_comments(s, JsDialect::JS, false);
+    assert_eq!(result.comments[0].data.as_deref(), Some("{\"version\":3}"));
+
+    let s = "//# sourceMappingURL=data:text/plain;base64,eyJ2ZXJzaW9uIjozfQ==";
+    let result = parse_source_map_comments(s, JsDialect::JS, false);
+    assert_eq!(result.comments[0].data, None);
+}
@@ -0,0 +1,111 @@
+use fmu::{guess_js_syntax, JsDialect, JsSyntax, JsSyntaxStream};
+use std::fs;
+
+fn stream(s: &str, chunk_size: usize) -> JsSyntax {
+    let mut stream = JsSyntaxStream::new(JsDialect::JS);
+    for chunk in s.as_bytes().chunks(chunk_size) {
+        stream.push(chunk);
+    }
+    stream.finish()
+}
+
+#[test]
+fn stream_matches_guess() {
+    for entry in fs::read_dir("tests/unit/samples").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "js") {
+            continue;
+        }
+        let s = fs::read_to_string(&path).unwrap();
+        for chunk_size in [1, 7, 64, s.len().max(1)] {
+            assert_eq!(
+                stream(&s, chunk_size),
+                guess_js_syntax(&s),
+                "{} in chunks of {}",
+                path.display(),
+                chunk_size
+            );
+        }
+    }
+}
+
+#[test]
+fn stream_across_chunks() {
+    // a template literal and a multi-byte character cut in the middle
+    let s = "const a = `\nrequire('a')\n${b}`\nconst c = 'é'\nexport default c\n";
+    assert_eq!(stream(s, 1), JsSyntax::ESM);
+    assert_eq!(stream(s, 12), JsSyntax::ESM);
+
+    // a top-level declaration shadows later parts
+    let s = "var require = () => {}\nrequire('a')\nexport {}\n";
+    assert_eq!(stream(s, 4), JsSyntax::ESM);
+}
+
+#[test]
+fn stream_early_answer() {
+    let mut stream = JsSyntaxStream::new(JsDialect::JS);
+    assert!(!stream.push(b"import a from 'a'\n"));
+    assert_eq!(stream.syntax(), JsSyntax::ESM);
+    assert!(stream.push(b"module.exports = a\n"));
+    assert!(stream.push(b"anything"));
+    assert_eq!(stream.finish(), JsSyntax::Mixed);
+}
+
+#[test]
+fn stream_invalid_utf8() {
+    let mut stream = JsSyntaxStream::new(JsDialect::JS);
+    stream.push(b"const a = '\xff'\n");
+    stream.push(b"require('a')");
+    assert_eq!(stream.finish(), JsSyntax::CJS);
+}
+
+// a webpack style bundle, everything is within one function
+fn bundle(modules: usize, body: &str) -> String {
+    let mut s = String::from("(() => {\n  var __webpack_modules__ = {\n");
+    for i in 0..modules {
+        s.push_str(&format!(
+            "    \"./src/{i}.js\": (module, exports, __webpack_require__) => {{\n      \
+             const value = {{ id: {i}, name: 'module {i}' }};\n      \
+             module.exports = value;\n    }},\n"
+        ));
+    }
+    s.push_str("  };\n");
+    s.push_str(body);
+    s.push_str("})();\n");
+    s
+}
+
+#[test]
+fn stream_wrapped_bundle() {
+    // the params shadow module and exports in every part
+    let s = bundle(500, "  __webpack_require__('./src/0.js');\n");
+    assert_eq!(guess_js_syntax(&s), JsSyntax::Unknown);
+    assert_eq!(stream(&s, 1000), JsSyntax::Unknown);
+
+    // the answer comes before the end of the wrapper
+    let s = bundle(
+        500,
+        "  const fs = require('fs');\n  console.log(import.meta.url);\n",
+    );
+    let s = format!("{}{}", s, "// padding\n".repeat(10000));
+    let mut stream = JsSyntaxStream::new(JsDialect::JS);
+    let chunks: Vec<&[u8]> = s.as_bytes().chunks(1000).collect();
+    let done = chunks.iter().position(|chunk| stream.push(chunk));
+    assert!(done.is_some_and(|i| i < chunks.len() / 2));
+    assert_eq!(stream.finish(), JsSyntax::Mixed);
+}
+
+#[test]
+fn stream_wrapped_declarations() {
+    // declared in an earlier part of the same function
+    let filler = "  var a = 1;\n".repeat(1000);
+    let s = format!("(function () {{\n  var require = load();\n{filler}  require('a');\n  export {{}};\n}})();\n");
+    assert_eq!(stream(&s, 500), guess_js_syntax(&s));
+    assert_eq!(stream(&s, 500), JsSyntax::ESM);
+
+    // but not in a function that's closed
+    let s = format!(
+        "(function () {{\n  function f(require) {{\n{filler}  }}\n  require('a');\n}})();\n"
+    );
+    assert_eq!(stream(&s, 500), JsSyntax::CJS);
+}
@@ -0,0 +1,131 @@
+use fmu::*;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+// pieces that change how the walker, lexer and scopes read the rest of the input
+const FRAGMENTS: [&str; 48] = [
+    "import", "export", "require", "module", "exports", "define", "await", "function", "class",
+    "var", "let", "const", "foo", "(", ")", "[", "]", "{", "}", "=", "=>", ",", ";", ":", ".", "'",
+    "\"", "`", "${", "/", "/*", "*/", "//", "<!--", "-->", "#!", "<a>", "</a>", "\\", "\n", "\r\n",
+    " ", "1", "é", "😀", "\u{FEFF}", "@", "*",
+];
+
+// inputs that used to panic or hang
+const REGRESSIONS: [&str; 10] = [
+    "foo([1])\n{\n  bar()\n}",
+    "var [1] = x",
+    "let [0] = 1",
+    "function f([1]) {}",
+    "const {a: \"x\"} = y",
+    "var[`",
+    "export const [1] = x",
+    "export const { a: 1 } = x",
+    "const café = 1 // hi",
+    "'a\\\r\nb'",
+];
+
+// xorshift, so failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 as usize
+    }
+}
+
+fn inputs() -> Vec<Vec<u8>> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut inputs: Vec<Vec<u8>> = REGRESSIONS.iter().map(|s| s.as_bytes().to_vec()).collect();
+    for _ in 0..1500 {
+        let mut input = Vec::new();
+        for _ in 0..rng.next() % 24 {
+            input.extend_from_slice(FRAGMENTS[rng.next() % FRAGMENTS.len()].as_bytes());
+        }
+        inputs.push(input);
+    }
+    // arbitrary bytes, including invalid utf-8
+    for _ in 0..300 {
+        let len = rng.next() % 32;
+        inputs.push((0..len).map(|_| rng.next() as u8).collect());
+    }
+    inputs
+}
+
+fn run_all(b: &[u8]) {
+    let s = String::from_utf8_lossy(b);
+    for dialect in [JsDialect::JS, JsDialect::TS, JsDialect::JSX, JsDialect::TSX] {
+        guess_js_syntax_with_dialect(&s, dialect);
+        guess_js_syntax_with_confidence(&s, dialect);
+        guess_js_format_with_dialect(&s, dialect);
+        analyze_js_syntax_with_dialect(&s, dialect);
+        parse_comments(&s, dialect);
+        parse_source_map_comments(&s, dialect, true);
+        Lexer::with_dialect(&s, dialect).for_each(drop);
+
+        guess_js_syntax_bytes(b, dialect);
+        guess_js_syntax_with_confidence_bytes(b, dialect);
+        guess_js_format_bytes(b, dialect);
+        analyze_js_syntax_bytes(b, dialect);
+        parse_comments_bytes(b, dialect);
+        parse_source_map_comments_bytes(b, dialect, true);
+        parse_esm(&s, dialect);
+        parse_cjs(&s, dialect);
+        parse_requires(&s, dialect);
+        rewrite_specifiers(&s, dialect, |v| Some(v.to_string()));
+        parse_esm_bytes(b, dialect);
+        parse_cjs_bytes(b, dialect);
+        parse_requires_bytes(b, dialect);
+
+        let mut stream = JsSyntaxStream::new(dialect);
+        for chunk in b.chunks(7) {
+            stream.push(chunk);
+        }
+        stream.finish();
+    }
+    parse_directives(&s);
+    parse_hashbang(&s);
+    directives_bytes(b);
+    hashbang_bytes(b);
+    check_node_format("a.js", &s, Some(s.to_string()));
+    check_node_format_bytes("a.ts", b, None);
+    parse_json(&s);
+    resolve_package(&s, "pkg/a", vec!["import".to_string()]);
+    resolve_package_bytes(b, "pkg/a", vec!["import".to_string()]);
+}
+
+#[test]
+fn totality() {
+    let inputs = inputs();
+    let (tx, rx) = mpsc::channel();
+    let worker = {
+        let inputs = inputs.clone();
+        thread::spawn(move || {
+            for (i, input) in inputs.iter().enumerate() {
+                tx.send(i).unwrap();
+                run_all(input);
+            }
+        })
+    };
+
+    let mut current = None;
+    loop {
+        match rx.recv_timeout(Duration::from_secs(10)) {
+            Ok(i) => current = Some(i),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            Err(mpsc::RecvTimeoutError::Timeout) => panic!(
+                "timed out on {:?}",
+                current.map(|i| String::from_utf8_lossy(&inputs[i]).into_owned())
+            ),
+        }
+    }
+    if worker.join().is_err() {
+        panic!(
+            "panicked on {:?}",
+            current.map(|i| String::from_utf8_lossy(&inputs[i]).into_owned())
+        );
+    }
+}
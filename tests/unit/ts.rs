@@ -0,0 +1,66 @@
+use crate::rs_ext;
+use fmu::{analyze_js_syntax_with_dialect, guess_js_syntax_with_dialect, JsDialect};
+use fmu::{guess_js_syntax, parse_cjs, parse_esm, parse_requires, JsSyntax, JsSyntaxEvidenceKind};
+
+#[test]
+fn import_equals() {
+    let s = rs_ext("ts_import_equals", "ts");
+    assert_eq!(guess_ts(&s), JsSyntax::CJS);
+    let kinds: Vec<_> = analyze_js_syntax_with_dialect(&s, JsDialect::TS)
+        .evidence
+        .iter()
+        .map(|e| e.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            JsSyntaxEvidenceKind::TsImportEquals,
+            JsSyntaxEvidenceKind::TsTypeImport,
+            JsSyntaxEvidenceKind::TsTypeExport,
+            JsSyntaxEvidenceKind::TsTypeExport,
+            JsSyntaxEvidenceKind::TsExportEquals,
+        ]
+    );
+}
+
+#[test]
+fn esm() {
+    assert_eq!(guess_ts(&rs_ext("ts_esm", "ts")), JsSyntax::ESM);
+}
+
+#[test]
+fn declare_module() {
+    assert_eq!(guess_ts(&rs_ext("ts_declare_module", "ts")), JsSyntax::CJS);
+}
+
+#[test]
+fn generics_division() {
+    // without typescript mode, `/ 2 + g('/` is a regex and the import is hidden in a string
+    let s = "const a = f<T>(x) / 2 + g('/'); import x from 'y'";
+    assert_eq!(guess_ts(s), JsSyntax::ESM);
+    assert_eq!(guess_js_syntax(s), JsSyntax::Unknown);
+    let imports = parse_esm(s, JsDialect::TS).imports;
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].specifier.as_deref(), Some("y"));
+    assert!(parse_esm(s, JsDialect::JS).imports.is_empty());
+
+    let s = "const a = f<T>(x) / 2 + g('/'); const b = require('y')";
+    let requires = parse_requires(s, JsDialect::TS);
+    assert_eq!(requires.len(), 1);
+    assert_eq!(requires[0].specifier.as_deref(), Some("y"));
+    assert!(parse_requires(s, JsDialect::JS).is_empty());
+    let s = "const a = f<T>(x) / 2 + g('/'); exports.b = 1";
+    assert_eq!(parse_cjs(s, JsDialect::TS).exports, vec!["b"]);
+    assert!(parse_cjs(s, JsDialect::JS).exports.is_empty());
+}
+
+#[test]
+fn dialect_from_extension() {
+    assert_eq!(JsDialect::from_extension("mts"), Some(JsDialect::TS));
+    assert_eq!(JsDialect::from_extension("cjs"), Some(JsDialect::JS));
+    assert_eq!(JsDialect::from_extension("css"), None);
+}
+
+fn guess_ts(s: &str) -> JsSyntax {
+    guess_js_syntax_with_dialect(s, JsDialect::TS)
+}